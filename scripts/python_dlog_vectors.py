@@ -0,0 +1,86 @@
+#
+# Generates test_vectors/python_dlog.json, the cross-language vectors for `zk_proof::compat`.
+#
+# The vectors are computed with `DLogProof` from ref.py and the htss_ecdsa serializer fields
+# it imports, so the Rust side is checked against the library itself. Every vector is verified
+# with `DLogProof.verify` before it is written, and the htss_ecdsa version used is printed.
+#
+# Nonces are fixed instead of drawn from `secrets` so the vectors are reproducible. With
+# `--check`, the committed vectors are compared against the library instead of overwritten.
+#
+# Requires python-ecdsa and htss_ecdsa:
+#
+#   python3 scripts/python_dlog_vectors.py [--check]
+#
+
+import json
+import os
+import sys
+from hashlib import sha256
+from importlib.metadata import version
+
+sys.path.insert(0, os.path.join(os.path.dirname(os.path.abspath(__file__)), ".."))
+
+from htss_ecdsa.common.serializers import ECDSAPointField  # noqa: E402
+from ref import G, DLogProof, q  # noqa: E402
+
+PATH = "test_vectors/python_dlog.json"
+
+
+def point_to_bytes(point):
+    # The bytes `_hash_points` feeds to SHA-256 for a point.
+    return ECDSAPointField().to_bytes(point)
+
+
+def vector(sid, pid, x, r, base=G):
+    # `DLogProof.prove` with the nonce `r` instead of a random one.
+    y = x * base
+    t = r * base
+    c = DLogProof._hash_points(sid, pid, [base, y, t])
+    s = (r + c * x) % q
+    assert DLogProof(t, s).verify(sid, pid, y, base)
+    return {
+        "sid": sid,
+        "pid": pid,
+        "x": "%064x" % x,
+        "r": "%064x" % r,
+        "base": point_to_bytes(base).hex(),
+        "y": point_to_bytes(y).hex(),
+        "t": point_to_bytes(t).hex(),
+        "c": "%064x" % c,
+        "s": "%064x" % s,
+    }
+
+
+def vectors():
+    seed = int.from_bytes(sha256(b"zk_proof python vectors").digest(), "big")
+    cases = [
+        ("sid", 1),
+        ("sid", 0),
+        ("", 255),
+        ("session-é中", 256),
+        ("a" * 100, 4294967295),
+    ]
+    result = []
+    for i, (sid, pid) in enumerate(cases):
+        x = int.from_bytes(sha256(b"x" + bytes([i]) + seed.to_bytes(32, "big")).digest(), "big") % q
+        r = int.from_bytes(sha256(b"r" + bytes([i]) + seed.to_bytes(32, "big")).digest(), "big") % q
+        result.append(vector(sid, pid, x, r))
+    h = (int.from_bytes(sha256(b"H").digest(), "big") % q) * G
+    result.append(vector("sid", 2, 5, 7, base=h))
+    return result
+
+
+if __name__ == "__main__":
+    generated = vectors()
+    print("htss_ecdsa", version("htss_ecdsa"))
+    if "--check" in sys.argv[1:]:
+        with open(PATH, encoding="utf-8") as f:
+            committed = json.load(f)
+        if committed != generated:
+            sys.exit("%s differs from the vectors of htss_ecdsa" % PATH)
+        print("%s matches" % PATH)
+    else:
+        with open(PATH, "w", encoding="utf-8") as f:
+            json.dump(generated, f, indent=2, ensure_ascii=False)
+            f.write("\n")
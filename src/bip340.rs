@@ -0,0 +1,623 @@
+//! BIP-340 Schnorr signatures over secp256k1 with x-only public keys.
+//!
+//! A [`DLogProof`](crate::DLogProof) is a Schnorr proof of knowledge of a key; the signatures
+//! here prove the same knowledge bound to a message, in the exact format Bitcoin uses since
+//! Taproot:
+//!
+//! * Public keys are the 32-byte x coordinate of a point with an even y coordinate. A secret key
+//!   whose point has an odd y is negated before signing.
+//! * Signatures are the 32-byte x coordinate of the even-y nonce point `R` followed by the
+//!   32-byte response `s`, with `s*G == R + e*P`.
+//! * Every hash is a tagged hash `SHA256(SHA256(tag) || SHA256(tag) || msg)`, which separates
+//!   the nonce, the auxiliary randomness and the challenge `e` from each other and from other
+//!   protocols.
+//!
+//! Signing and verification match the official BIP-340 test vectors, which are kept in
+//! `test_vectors/bip340.csv`.
+//!
+//! ```
+//! use zk_proof::{bip340, SecretScalar};
+//!
+//! let secret = SecretScalar::random(&mut rand::thread_rng());
+//! let public_key = bip340::XOnlyPublicKey::from_secret(&secret).unwrap();
+//! let signature = bip340::sign(&secret, b"message").unwrap();
+//!
+//! let decoded = bip340::Signature::from_bytes(&signature.to_bytes()).unwrap();
+//! assert!(decoded.verify(public_key, b"message").is_ok());
+//! ```
+
+use k256::{
+    elliptic_curve::{
+        group::{ff::Field, Group},
+        ops::Reduce,
+        point::AffineCoordinates,
+        rand_core::CryptoRngCore,
+        zeroize::Zeroizing,
+    },
+    ProjectivePoint, Scalar, U256,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    encoding::{decode_scalar, decode_sec1},
+    msm::msm,
+    SecretScalar, ZkError,
+};
+
+const AUX_TAG: &[u8] = b"BIP0340/aux";
+const NONCE_TAG: &[u8] = b"BIP0340/nonce";
+const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+
+/// A public key `P` with an even y coordinate, encoded as its x coordinate only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XOnlyPublicKey(ProjectivePoint);
+
+impl XOnlyPublicKey {
+    /// Returns the key of `point`, which is `point` itself if its y coordinate is even and
+    /// `-point` otherwise, as both share the x coordinate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `point` is the point at infinity.
+    pub fn new(point: ProjectivePoint) -> Result<Self, ZkError> {
+        if bool::from(point.is_identity()) {
+            return Err(ZkError::IdentityPoint);
+        }
+        Ok(Self(even_y(point).0))
+    }
+
+    /// Returns the key of the secret key `d`, i.e. of the point `d*G`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `d` is zero.
+    pub fn from_secret(secret: &SecretScalar) -> Result<Self, ZkError> {
+        Ok(Self(key_pair(secret)?.1))
+    }
+
+    /// Returns the point `P` with an even y coordinate.
+    pub fn point(&self) -> ProjectivePoint {
+        self.0
+    }
+
+    /// Encodes the key as the 32-byte big-endian x coordinate of `P`.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        x_bytes(&self.0)
+    }
+
+    /// Decodes a key encoded with [`XOnlyPublicKey::to_bytes`], i.e. `lift_x` of BIP-340.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `bytes` is not 32 bytes long and
+    /// [`ZkError::MalformedPoint`] if it is not the x coordinate of a point on the curve.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        lift_x(bytes).map(Self)
+    }
+}
+
+/// A BIP-340 signature `(r, s)`, where `r` is the x coordinate of the nonce point `R`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    r: [u8; 32],
+    s: Scalar,
+}
+
+impl Signature {
+    /// Creates a signature from the x coordinate `r` of `R` and the response `s`.
+    pub fn new(r: [u8; 32], s: Scalar) -> Self {
+        Self { r, s }
+    }
+
+    /// Returns the x coordinate of the nonce point `R`.
+    pub fn r(&self) -> &[u8; 32] {
+        &self.r
+    }
+
+    /// Returns the response `s`.
+    pub fn s(&self) -> &Scalar {
+        &self.s
+    }
+
+    /// Encodes the signature as the 64 bytes `r || s`.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s.to_bytes());
+        bytes
+    }
+
+    /// Decodes a signature encoded with [`Signature::to_bytes`].
+    ///
+    /// `r` is not checked here; a value that is not the x coordinate of a point fails
+    /// verification.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `bytes` is not 64 bytes long and
+    /// [`ZkError::NonCanonicalScalar`] if `s` is not smaller than the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        if bytes.len() != 64 {
+            return Err(ZkError::Encoding(format!(
+                "expected 64 signature bytes, got {}",
+                bytes.len()
+            )));
+        }
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        Ok(Self::new(r, decode_scalar(&bytes[32..])?))
+    }
+
+    /// Verifies the signature of `msg` under `public_key`.
+    ///
+    /// Computes `R = s*G - e*P` for the challenge
+    /// `e = hash_BIP0340/challenge(r || P || msg)` and checks that `R` is not the point at
+    /// infinity, has an even y coordinate and has the x coordinate `r`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::ChallengeMismatch`] if the signature does not hold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{bip340, SecretScalar};
+    /// let secret = SecretScalar::random(&mut rand::thread_rng());
+    /// let public_key = bip340::XOnlyPublicKey::from_secret(&secret).unwrap();
+    /// let signature = bip340::sign(&secret, b"message").unwrap();
+    /// assert!(signature.verify(public_key, b"message").is_ok());
+    /// assert!(signature.verify(public_key, b"other").is_err());
+    /// ```
+    pub fn verify(&self, public_key: XOnlyPublicKey, msg: &[u8]) -> Result<(), ZkError> {
+        let e = challenge(&self.r, &public_key.to_bytes(), msg);
+        let r = ProjectivePoint::GENERATOR * self.s - public_key.0 * e;
+        if bool::from(r.is_identity()) {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        let affine = r.to_affine();
+        if bool::from(affine.y_is_odd()) || affine.x().as_slice() != self.r.as_slice() {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verifies many signatures at once.
+    ///
+    /// Follows the batch verification of BIP-340: the equations `s_i*G == R_i + e_i*P_i` are
+    /// combined with random weights `a_i`, with `a_1 = 1`, into a single multi-scalar
+    /// multiplication, which holds for an invalid signature only with negligible probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `(public_key, msg, signature)` tuples to verify.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if every signature is valid, otherwise `Err(i)` with the index of the first
+    /// invalid signature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{bip340, SecretScalar};
+    /// let items: Vec<_> = (0..3)
+    ///     .map(|_| {
+    ///         let secret = SecretScalar::random(&mut rand::thread_rng());
+    ///         let public_key = bip340::XOnlyPublicKey::from_secret(&secret).unwrap();
+    ///         let msg: &[u8] = b"message";
+    ///         (public_key, msg, bip340::sign(&secret, msg).unwrap())
+    ///     })
+    ///     .collect();
+    /// assert_eq!(bip340::Signature::batch_verify(&items), Ok(()));
+    /// ```
+    pub fn batch_verify(items: &[(XOnlyPublicKey, &[u8], Signature)]) -> Result<(), usize> {
+        Self::batch_verify_with_rng(&mut rand::thread_rng(), items)
+    }
+
+    /// Verifies many signatures like [`Signature::batch_verify`] with the random weights drawn
+    /// from `rng`.
+    pub fn batch_verify_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        items: &[(XOnlyPublicKey, &[u8], Signature)],
+    ) -> Result<(), usize> {
+        let mut scalars = Vec::with_capacity(2 * items.len() + 1);
+        let mut points = Vec::with_capacity(2 * items.len() + 1);
+        let mut s_sum = Scalar::ZERO;
+        let mut failed = false;
+        for (index, (public_key, msg, signature)) in items.iter().enumerate() {
+            // An earlier signature may fail only the combined equation, so a signature whose
+            // `r` does not lift is left to the scan below rather than reported right away.
+            let Ok(r) = lift_x(&signature.r) else {
+                failed = true;
+                break;
+            };
+            let a = if index == 0 {
+                Scalar::ONE
+            } else {
+                Scalar::random(&mut *rng)
+            };
+            let e = challenge(&signature.r, &public_key.to_bytes(), msg);
+            s_sum += a * signature.s;
+            scalars.extend([-a, -(a * e)]);
+            points.extend([r, public_key.0]);
+        }
+        scalars.push(s_sum);
+        points.push(ProjectivePoint::GENERATOR);
+
+        if !failed && bool::from(msm(&scalars, &points).is_identity()) {
+            return Ok(());
+        }
+        match items
+            .iter()
+            .position(|(public_key, msg, signature)| signature.verify(*public_key, msg).is_err())
+        {
+            Some(index) => Err(index),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Signs `msg` with the secret key `d`, drawing the auxiliary randomness from the thread-local
+/// generator.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `d` is zero.
+pub fn sign(secret: &SecretScalar, msg: &[u8]) -> Result<Signature, ZkError> {
+    sign_with_rng(&mut rand::thread_rng(), secret, msg)
+}
+
+/// Signs `msg` like [`sign`] with the auxiliary randomness drawn from `rng`.
+pub fn sign_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    secret: &SecretScalar,
+    msg: &[u8],
+) -> Result<Signature, ZkError> {
+    let mut aux_rand = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(aux_rand.as_mut());
+    sign_with_aux_rand(secret, msg, &aux_rand)
+}
+
+/// Signs `msg` with the secret key `d` and the auxiliary randomness `a`, following the default
+/// signing algorithm of BIP-340.
+///
+/// The nonce is derived from `d`, `a`, the public key and `msg`, so the signature is
+/// deterministic for a fixed `a` and stays secure if `a` is not random. Fresh randomness still
+/// protects against side-channel and fault attacks.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `d` is zero.
+pub fn sign_with_aux_rand(
+    secret: &SecretScalar,
+    msg: &[u8],
+    aux_rand: &[u8; 32],
+) -> Result<Signature, ZkError> {
+    let (d, public_key) = key_pair(secret)?;
+    let p = x_bytes(&public_key);
+
+    let mut t = Zeroizing::new(d.expose_secret().to_bytes());
+    for (byte, mask) in t.iter_mut().zip(tagged_hash(AUX_TAG, &[aux_rand])) {
+        *byte ^= mask;
+    }
+    let rand = Zeroizing::new(tagged_hash(NONCE_TAG, &[t.as_slice(), &p, msg]));
+    let k = SecretScalar::new(reduce(&rand));
+    if bool::from(k.expose_secret().is_zero()) {
+        // Happens with negligible probability only.
+        return Err(ZkError::InvalidInput(
+            "the derived nonce is zero".to_string(),
+        ));
+    }
+    let (r, negated) = even_y(ProjectivePoint::GENERATOR * k.expose_secret());
+    let k = if negated {
+        SecretScalar::new(-k.expose_secret())
+    } else {
+        k
+    };
+    let r = x_bytes(&r);
+    let e = challenge(&r, &p, msg);
+    Ok(Signature::new(
+        r,
+        *k.expose_secret() + e * d.expose_secret(),
+    ))
+}
+
+/// Computes `SHA256(SHA256(tag) || SHA256(tag) || msg)` for the concatenation `msg` of `parts`.
+pub(crate) fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new().chain_update(tag_hash).chain_update(tag_hash);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Derives the challenge `e = hash_BIP0340/challenge(r || P || msg) mod n`.
+pub(crate) fn challenge(r: &[u8; 32], public_key: &[u8; 32], msg: &[u8]) -> Scalar {
+    reduce(&tagged_hash(CHALLENGE_TAG, &[r, public_key, msg]))
+}
+
+/// Interprets a hash as a big-endian integer modulo the group order.
+pub(crate) fn reduce(hash: &[u8; 32]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(hash.into())
+}
+
+/// Returns the secret key negated if needed so that its point has an even y coordinate,
+/// together with that point.
+fn key_pair(secret: &SecretScalar) -> Result<(SecretScalar, ProjectivePoint), ZkError> {
+    if bool::from(secret.expose_secret().is_zero()) {
+        return Err(ZkError::InvalidInput("the secret key is zero".to_string()));
+    }
+    let (point, negated) = even_y(ProjectivePoint::GENERATOR * secret.expose_secret());
+    let d = if negated {
+        SecretScalar::new(-secret.expose_secret())
+    } else {
+        secret.clone()
+    };
+    Ok((d, point))
+}
+
+/// Returns `point` or `-point`, whichever has an even y coordinate, and whether it negated.
+pub(crate) fn even_y(point: ProjectivePoint) -> (ProjectivePoint, bool) {
+    if bool::from(point.to_affine().y_is_odd()) {
+        (-point, true)
+    } else {
+        (point, false)
+    }
+}
+
+pub(crate) fn x_bytes(point: &ProjectivePoint) -> [u8; 32] {
+    point.to_affine().x().into()
+}
+
+/// Returns the point with the x coordinate `bytes` and an even y coordinate.
+fn lift_x(bytes: &[u8]) -> Result<ProjectivePoint, ZkError> {
+    if bytes.len() != 32 {
+        return Err(ZkError::Encoding(format!(
+            "expected 32 x-only key bytes, got {}",
+            bytes.len()
+        )));
+    }
+    let mut compressed = [0x02; 33];
+    compressed[1..].copy_from_slice(bytes);
+    decode_sec1::<k256::Secp256k1>(&compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    struct Vector {
+        index: usize,
+        secret_key: Option<SecretScalar>,
+        public_key: Vec<u8>,
+        aux_rand: Option<[u8; 32]>,
+        message: Vec<u8>,
+        signature: Vec<u8>,
+        valid: bool,
+    }
+
+    fn vectors() -> Vec<Vector> {
+        include_str!("../test_vectors/bip340.csv")
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let fields: Vec<_> = line.splitn(8, ',').collect();
+                let bytes = |field: &str| hex_to_bytes(field).unwrap();
+                Vector {
+                    index: fields[0].parse().unwrap(),
+                    secret_key: (!fields[1].is_empty())
+                        .then(|| SecretScalar::new(decode_scalar(&bytes(fields[1])).unwrap())),
+                    public_key: bytes(fields[2]),
+                    aux_rand: (!fields[3].is_empty()).then(|| bytes(fields[3]).try_into().unwrap()),
+                    message: bytes(fields[4]),
+                    signature: bytes(fields[5]),
+                    valid: fields[6] == "TRUE",
+                }
+            })
+            .collect()
+    }
+
+    fn verifies(vector: &Vector) -> bool {
+        match (
+            XOnlyPublicKey::from_bytes(&vector.public_key),
+            Signature::from_bytes(&vector.signature),
+        ) {
+            (Ok(public_key), Ok(signature)) => {
+                signature.verify(public_key, &vector.message).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_sign_vectors() {
+        let vectors = vectors();
+        assert_eq!(vectors.len(), 19);
+        for vector in &vectors {
+            let (Some(secret_key), Some(aux_rand)) = (&vector.secret_key, &vector.aux_rand) else {
+                continue;
+            };
+            let public_key = XOnlyPublicKey::from_secret(secret_key).unwrap();
+            assert_eq!(
+                public_key.to_bytes().as_slice(),
+                vector.public_key,
+                "vector {}",
+                vector.index
+            );
+            let signature = sign_with_aux_rand(secret_key, &vector.message, aux_rand).unwrap();
+            assert_eq!(
+                signature.to_bytes().as_slice(),
+                vector.signature,
+                "vector {}",
+                vector.index
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_vectors() {
+        for vector in vectors() {
+            assert_eq!(verifies(&vector), vector.valid, "vector {}", vector.index);
+        }
+    }
+
+    #[test]
+    fn test_batch_verify_vectors() {
+        let vectors = vectors();
+        let mut items: Vec<_> = vectors
+            .iter()
+            .filter(|vector| vector.valid)
+            .map(|vector| {
+                (
+                    XOnlyPublicKey::from_bytes(&vector.public_key).unwrap(),
+                    vector.message.as_slice(),
+                    Signature::from_bytes(&vector.signature).unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(Signature::batch_verify(&items), Ok(()));
+
+        // Invalid signatures whose key and signature decode, among them those where `r` is not
+        // an x coordinate (11, 12) or `R` is infinite (9, 10).
+        for vector in vectors
+            .iter()
+            .filter(|vector| !vector.valid && ![5, 13, 14].contains(&vector.index))
+        {
+            let invalid = (
+                XOnlyPublicKey::from_bytes(&vector.public_key).unwrap(),
+                vector.message.as_slice(),
+                Signature::from_bytes(&vector.signature).unwrap(),
+            );
+            for position in [0, 3, items.len()] {
+                items.insert(position, invalid);
+                assert_eq!(
+                    Signature::batch_verify(&items),
+                    Err(position),
+                    "vector {}",
+                    vector.index
+                );
+                items.remove(position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_verify_reports_first_of_two_invalid() {
+        let vectors = vectors();
+        fn item(vector: &Vector) -> (XOnlyPublicKey, &[u8], Signature) {
+            (
+                XOnlyPublicKey::from_bytes(&vector.public_key).unwrap(),
+                vector.message.as_slice(),
+                Signature::from_bytes(&vector.signature).unwrap(),
+            )
+        }
+        let mut items: Vec<_> = vectors
+            .iter()
+            .filter(|vector| vector.valid)
+            .map(item)
+            .collect();
+        // Vector 6 fails only the equation, vector 11 already fails to lift `r`.
+        items.insert(1, item(&vectors[6]));
+        items.insert(3, item(&vectors[11]));
+        assert_eq!(Signature::batch_verify(&items), Err(1));
+    }
+
+    #[test]
+    fn test_odd_secret_key_negated() {
+        // The point of the secret key 1 is G, whose y coordinate is even, so -1 has an odd one.
+        let secret = SecretScalar::new(-Scalar::ONE);
+        let public_key = XOnlyPublicKey::from_secret(&secret).unwrap();
+        assert_eq!(public_key.point(), ProjectivePoint::GENERATOR);
+        assert_eq!(
+            XOnlyPublicKey::new(-ProjectivePoint::GENERATOR),
+            Ok(public_key)
+        );
+        let signature = sign(&secret, b"message").unwrap();
+        assert!(signature.verify(public_key, b"message").is_ok());
+    }
+
+    #[test]
+    fn test_sign_rejects_zero_key() {
+        let zero = SecretScalar::new(Scalar::ZERO);
+        assert!(matches!(
+            sign(&zero, b"message"),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            XOnlyPublicKey::from_secret(&zero),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert_eq!(
+            XOnlyPublicKey::new(ProjectivePoint::IDENTITY),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_other_key_or_message() {
+        let secret = SecretScalar::random(&mut rand::thread_rng());
+        let public_key = XOnlyPublicKey::from_secret(&secret).unwrap();
+        let other =
+            XOnlyPublicKey::from_secret(&SecretScalar::random(&mut rand::thread_rng())).unwrap();
+        let signature = sign(&secret, b"message").unwrap();
+        assert!(signature.verify(public_key, b"message").is_ok());
+        assert_eq!(
+            signature.verify(other, b"message"),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            signature.verify(public_key, b"other"),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_aux_rand_changes_nonce() {
+        let secret = SecretScalar::random(&mut rand::thread_rng());
+        let first = sign_with_aux_rand(&secret, b"message", &[0; 32]).unwrap();
+        assert_eq!(
+            sign_with_aux_rand(&secret, b"message", &[0; 32]).unwrap(),
+            first
+        );
+        assert_ne!(
+            sign_with_aux_rand(&secret, b"message", &[1; 32]).unwrap(),
+            first
+        );
+    }
+
+    #[test]
+    fn test_decoding_errors() {
+        assert!(matches!(
+            Signature::from_bytes(&[0; 63]),
+            Err(ZkError::Encoding(_))
+        ));
+        assert!(matches!(
+            XOnlyPublicKey::from_bytes(&[0; 33]),
+            Err(ZkError::Encoding(_))
+        ));
+        let mut high_s = [0u8; 64];
+        high_s[32..].fill(0xff);
+        assert_eq!(
+            Signature::from_bytes(&high_s),
+            Err(ZkError::NonCanonicalScalar)
+        );
+        assert_eq!(
+            XOnlyPublicKey::from_bytes(&[0xff; 32]),
+            Err(ZkError::MalformedPoint)
+        );
+    }
+
+    #[test]
+    fn test_tagged_hash() {
+        let tag_hash = Sha256::digest(CHALLENGE_TAG);
+        let expected: [u8; 32] = Sha256::new()
+            .chain_update(tag_hash)
+            .chain_update(tag_hash)
+            .chain_update(b"ab")
+            .finalize()
+            .into();
+        assert_eq!(tagged_hash(CHALLENGE_TAG, &[b"a", b"b"]), expected);
+    }
+}
@@ -0,0 +1,403 @@
+use k256::{
+    elliptic_curve::{group::Group, rand_core::CryptoRngCore, PrimeField},
+    ProjectivePoint,
+};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha256;
+
+use crate::{
+    dlog::PROOF,
+    encoding::{decode_scalar, Bytes, Encoded, EncodedProof, Encoding, PairVisitor},
+    group::{ensure_non_identity, scalar_len},
+    hash::ZkDigest,
+    sigma::{Schnorr, SigmaProtocol},
+    transcript::{self, Transcript},
+    DLogProof, SecretScalar, ZkError, ZkGroup,
+};
+
+/// [`DLogProof`] in its compact form, holding the challenge `c` instead of the commitment `t`.
+///
+/// Both scalars take 32 bytes on secp256k1, so the proof is 64 bytes instead of the 65 bytes of
+/// `(t, s)`, and the serialized form needs no point validation. The verifier recomputes
+/// `t = s*G - c*y` and checks that it hashes to `c`. A compact proof accepts exactly when the
+/// corresponding [`DLogProof`] does, and both forms convert losslessly into each other given
+/// the statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactDLogProof<P: Group = ProjectivePoint> {
+    c: P::Scalar,
+    s: P::Scalar,
+}
+
+impl<P: ZkGroup> CompactDLogProof<P> {
+    /// Creates a proof from its challenge `c` and response `s`.
+    pub fn new(c: P::Scalar, s: P::Scalar) -> Self {
+        Self { c, s }
+    }
+
+    /// Returns the challenge scalar `c`.
+    pub fn c(&self) -> &P::Scalar {
+        &self.c
+    }
+
+    /// Returns the response scalar `s`.
+    pub fn s(&self) -> &P::Scalar {
+        &self.s
+    }
+
+    /// Generates a compact proof that the prover knows the discrete logarithm of `y`.
+    ///
+    /// Same as [`DLogProof::prove`], keeping `c` instead of `t`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::CompactDLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let proof = CompactDLogProof::prove("sid", 1, &x, y);
+    /// assert!(proof.verify("sid", 1, y).is_ok());
+    /// ```
+    pub fn prove(sid: &str, pid: u32, x: &SecretScalar<P::Scalar>, y: P) -> Self {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, x, y)
+    }
+
+    /// Generates a proof like [`CompactDLogProof::prove`] with the nonce `r` drawn from `rng`.
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+    ) -> Self {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, x, y, P::generator())
+    }
+
+    /// Generates a compact proof like [`DLogProof::prove_with_transcript`].
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+        base_point: P,
+    ) -> Self {
+        let statement = Schnorr::new(base_point, y);
+        let (t, r) = statement
+            .commit(x, rng)
+            .expect("a Schnorr commitment does not fail");
+        let c = statement.challenge(transcript, &t);
+        Self::new(c, statement.respond(x, r, &c))
+    }
+
+    /// Verifies that `t = s*G - c*y` hashes to `c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `y` - The public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `y` or the recomputed `t` is the point at infinity
+    /// and [`ZkError::ChallengeMismatch`] if `t` does not hash to `c`.
+    pub fn verify(&self, sid: &str, pid: u32, y: P) -> Result<(), ZkError> {
+        self.verify_with_base(sid, pid, y, P::generator())
+    }
+
+    /// Verifies a compact proof for `y = x*base_point` like [`DLogProof::verify_with_base`].
+    pub fn verify_with_base(
+        &self,
+        sid: &str,
+        pid: u32,
+        y: P,
+        base_point: P,
+    ) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, y, base_point)
+    }
+
+    /// Verifies a compact proof like [`DLogProof::verify_with_transcript`].
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        y: P,
+        base_point: P,
+    ) -> Result<(), ZkError> {
+        let t = self.commitment(y, base_point)?;
+        if transcript::challenge(transcript, PROOF, &[base_point, y, t]) != self.c {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Converts the proof into its `(t, s)` form by recomputing `t = s*G - c*y`.
+    ///
+    /// The result verifies if and only if this proof does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `y` or `t` is the point at infinity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{CompactDLogProof, DLogProof};
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+    /// let compact = dlog_proof.to_compact("sid", 1, y);
+    /// assert_eq!(compact.to_dlog_proof(y), Ok(dlog_proof));
+    /// ```
+    pub fn to_dlog_proof(&self, y: P) -> Result<DLogProof<P>, ZkError> {
+        self.to_dlog_proof_with_base(y, P::generator())
+    }
+
+    /// Converts a proof for `y = x*base_point` into its `(t, s)` form by recomputing
+    /// `t = s*base_point - c*y`.
+    pub fn to_dlog_proof_with_base(&self, y: P, base_point: P) -> Result<DLogProof<P>, ZkError> {
+        Ok(DLogProof::new(self.commitment(y, base_point)?, self.s))
+    }
+
+    /// Recomputes the commitment `t = s*base - c*y`.
+    fn commitment(&self, y: P, base: P) -> Result<P, ZkError> {
+        ensure_non_identity(&[base, y])?;
+        let t = base * self.s - y * self.c;
+        ensure_non_identity(&[t])?;
+        Ok(t)
+    }
+
+    /// Serializes the proof as a struct with fields `c` and `s`, encoding the big-endian `c`
+    /// with `C` and the big-endian `s` with `S`.
+    pub fn serialize_with<C: Encoding, S: Encoding, Ser: Serializer>(
+        &self,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        let mut state = serializer.serialize_struct("CompactDLogProof", 2)?;
+        state.serialize_field("c", &Encoded::<C>::new(self.c.to_repr().as_ref()))?;
+        state.serialize_field("s", &Encoded::<S>::new(self.s.to_repr().as_ref()))?;
+        state.end()
+    }
+
+    /// Deserializes a proof written by [`CompactDLogProof::serialize_with`] with the same
+    /// encodings, rejecting scalars that are not smaller than the group order.
+    pub fn deserialize_with<'de, C: Encoding, S: Encoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let len = scalar_len::<P::Scalar>();
+        let visitor = PairVisitor::<C, S>::new("CompactDLogProof", FIELDS, [len, len]);
+        let (c, s) = deserializer.deserialize_struct("CompactDLogProof", FIELDS, visitor)?;
+        let c = decode_scalar(&c).map_err(serde::de::Error::custom)?;
+        let s = decode_scalar(&s).map_err(serde::de::Error::custom)?;
+        Ok(Self::new(c, s))
+    }
+}
+
+impl<P: ZkGroup> DLogProof<P> {
+    /// Converts the proof into its compact `(c, s)` form by recomputing the challenge `c`.
+    ///
+    /// The result verifies if and only if this proof does.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `y` - The public key.
+    pub fn to_compact(&self, sid: &str, pid: u32, y: P) -> CompactDLogProof<P> {
+        self.to_compact_with_base(sid, pid, y, P::generator())
+    }
+
+    /// Converts a proof generated by [`DLogProof::prove_with_base`] into its compact form.
+    pub fn to_compact_with_base(
+        &self,
+        sid: &str,
+        pid: u32,
+        y: P,
+        base_point: P,
+    ) -> CompactDLogProof<P> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.to_compact_with_transcript(&mut transcript, y, base_point)
+    }
+
+    /// Converts a proof generated by [`DLogProof::prove_with_transcript`] into its compact
+    /// form, deriving `c` from `transcript`.
+    pub fn to_compact_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        y: P,
+        base_point: P,
+    ) -> CompactDLogProof<P> {
+        let c = transcript::challenge(transcript, PROOF, &[base_point, y, *self.t()]);
+        CompactDLogProof::new(c, *self.s())
+    }
+}
+
+const FIELDS: &[&str; 2] = &["c", "s"];
+
+impl<P: ZkGroup> EncodedProof for CompactDLogProof<P> {
+    fn serialize_with<A: Encoding, B: Encoding, S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        CompactDLogProof::serialize_with::<A, B, S>(self, serializer)
+    }
+
+    fn deserialize_with<'de, A: Encoding, B: Encoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        CompactDLogProof::deserialize_with::<A, B, D>(deserializer)
+    }
+}
+
+/// Serializes `c` and `s` as [`Bytes`].
+impl<P: ZkGroup> Serialize for CompactDLogProof<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_with::<Bytes, Bytes, S>(serializer)
+    }
+}
+
+impl<'de, P: ZkGroup> Deserialize<'de> for CompactDLogProof<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize_with::<Bytes, Bytes, D>(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encoding::{as_hex, Decimal, Hex},
+        generate_random_number,
+    };
+    use k256::Scalar;
+
+    fn statement() -> (SecretScalar, ProjectivePoint) {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        (x, y)
+    }
+
+    #[test]
+    fn test_verify() {
+        let (x, y) = statement();
+        let proof = CompactDLogProof::prove("sid", 1, &x, y);
+        assert!(proof.verify("sid", 1, y).is_ok());
+        assert_eq!(proof.verify("abc", 1, y), Err(ZkError::ChallengeMismatch));
+        assert_eq!(proof.verify("sid", 2, y), Err(ZkError::ChallengeMismatch));
+        let other = ProjectivePoint::GENERATOR * generate_random_number();
+        assert_eq!(
+            proof.verify("sid", 1, other),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            proof.verify("sid", 1, ProjectivePoint::IDENTITY),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_conversion() {
+        let (x, y) = statement();
+        let h = ProjectivePoint::GENERATOR * generate_random_number();
+        let dlog_proof = DLogProof::prove_with_base("sid", 1, &x, h * x.expose_secret(), h);
+        let compact = dlog_proof.to_compact_with_base("sid", 1, h * x.expose_secret(), h);
+        assert!(compact
+            .verify_with_base("sid", 1, h * x.expose_secret(), h)
+            .is_ok());
+        assert_eq!(
+            compact.to_dlog_proof_with_base(h * x.expose_secret(), h),
+            Ok(dlog_proof)
+        );
+
+        let compact = CompactDLogProof::prove("sid", 1, &x, y);
+        let dlog_proof = compact.to_dlog_proof(y).unwrap();
+        assert!(dlog_proof.verify("sid", 1, y).is_ok());
+        assert_eq!(dlog_proof.to_compact("sid", 1, y), compact);
+    }
+
+    #[test]
+    fn test_conversion_preserves_invalidity() {
+        let (x, y) = statement();
+        let compact = CompactDLogProof::prove("sid", 1, &x, y);
+        let forged = CompactDLogProof::new(*compact.c(), *compact.s() + Scalar::ONE);
+        let dlog_proof = forged.to_dlog_proof(y).unwrap();
+        assert_eq!(
+            dlog_proof.verify("sid", 1, y),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_ne!(dlog_proof.to_compact("sid", 1, y), forged);
+    }
+
+    #[test]
+    fn test_transcript() {
+        let (x, y) = statement();
+        let g = ProjectivePoint::GENERATOR;
+        let transcript = Transcript::<sha2::Sha512>::with_digest(b"test");
+        let mut rng = rand::thread_rng();
+        let proof =
+            CompactDLogProof::prove_with_transcript(&mut rng, &mut transcript.clone(), &x, y, g);
+        assert!(proof
+            .verify_with_transcript(&mut transcript.clone(), y, g)
+            .is_ok());
+        let dlog_proof = proof.to_dlog_proof(y).unwrap();
+        assert_eq!(
+            dlog_proof.to_compact_with_transcript(&mut transcript.clone(), y, g),
+            proof
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let (x, y) = statement();
+        let proof = CompactDLogProof::prove("sid", 1, &x, y);
+
+        let bincode = bincode::serialize(&proof).unwrap();
+        assert_eq!(
+            bincode::deserialize::<CompactDLogProof>(&bincode).unwrap(),
+            proof
+        );
+        let full = bincode::serialize(&proof.to_dlog_proof(y).unwrap()).unwrap();
+        assert!(bincode.len() < full.len());
+
+        let mut json = vec![];
+        proof
+            .serialize_with::<Hex, Decimal, _>(&mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        let mut deserializer = serde_json::Deserializer::from_slice(&json);
+        let decoded = CompactDLogProof::deserialize_with::<Hex, Decimal, _>(&mut deserializer);
+        assert_eq!(decoded.unwrap(), proof);
+    }
+
+    #[test]
+    fn test_serde_with_adapter() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Message {
+            #[serde(with = "as_hex")]
+            full: DLogProof,
+            #[serde(with = "as_hex")]
+            compact: CompactDLogProof,
+        }
+
+        let (x, y) = statement();
+        let full = DLogProof::prove("sid", 1, &x, y);
+        let compact = full.to_compact("sid", 1, y);
+        let json = serde_json::to_string(&Message { full, compact }).unwrap();
+        let decoded: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.full, full);
+        assert_eq!(decoded.compact, compact);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_non_canonical() {
+        let json = format!(r#"{{"c":"{}","s":"{}"}}"#, "ff".repeat(32), "01".repeat(32));
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert!(
+            CompactDLogProof::<ProjectivePoint>::deserialize_with::<Hex, Hex, _>(&mut deserializer)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Byte-exact compatibility with the Python `htss_ecdsa` `DLogProof` (see `ref.py`).
+//!
+//! The Python `_hash_points` feeds the transcript through the `htss_ecdsa` serializer fields
+//! without the labels, length prefixes and domain separator of the
+//! [`Transcript`](crate::Transcript) used by [`DLogProof::hash_points`]:
+//!
+//! * `sid` is hashed as `StringField.to_bytes`, i.e. its UTF-8 bytes.
+//! * `pid` is hashed as `BigIntegerField.to_bytes`, i.e. the minimal big-endian encoding
+//!   (`0` encodes to no bytes at all).
+//! * Every point is hashed as `ECDSAPointField.to_bytes`, i.e. python-ecdsa's "raw" 64-byte
+//!   `x || y` encoding.
+//!
+//! The SHA-256 digest is read as a big-endian integer. Python keeps the challenge unreduced,
+//! which is equivalent to reducing it modulo the group order as done here.
+//!
+//! Proofs produced by this module verify with the Python `DLogProof.verify` and vice versa.
+//! `scripts/python_dlog_vectors.py` regenerates the vectors in `test_vectors/python_dlog.json`.
+
+use k256::{
+    elliptic_curve::{ops::Reduce, rand_core::CryptoRngCore, sec1::ToEncodedPoint},
+    ProjectivePoint, Scalar, U256,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{DLogProof, SecretScalar, ZkError};
+
+/// Encodes `n` like `BigIntegerField.to_bytes`: big-endian without leading zero bytes.
+fn bigint_to_bytes(n: u32) -> Vec<u8> {
+    let bytes = n.to_be_bytes();
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    bytes[skip..].to_vec()
+}
+
+/// Encodes `point` like `ECDSAPointField.to_bytes`: the raw 64-byte `x || y` coordinates.
+fn point_to_bytes(point: &ProjectivePoint) -> Vec<u8> {
+    let encoded = point.to_affine().to_encoded_point(false);
+    // Drop the SEC1 tag byte; the identity has no coordinates and encodes to nothing.
+    encoded.as_bytes().get(1..).unwrap_or_default().to_vec()
+}
+
+/// Computes the challenge exactly like the Python `DLogProof._hash_points`.
+///
+/// # Arguments
+///
+/// * `sid` - The session id.
+/// * `pid` - The id of the prover.
+/// * `points` - The points to be hashed.
+///
+/// # Returns
+///
+/// A `Scalar` representing the hash of the inputs.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::compat;
+/// # use k256::ProjectivePoint;
+/// let points = vec![ProjectivePoint::GENERATOR; 3];
+/// let hash = compat::hash_points("sid", 1, &points);
+/// ```
+pub fn hash_points(sid: &str, pid: u32, points: &[ProjectivePoint]) -> Scalar {
+    let mut hasher = Sha256::new();
+    hasher.update(sid.as_bytes());
+    hasher.update(bigint_to_bytes(pid));
+    for point in points {
+        hasher.update(point_to_bytes(point));
+    }
+    let result: &[u8] = &hasher.finalize();
+
+    <Scalar as Reduce<U256>>::reduce_bytes(result.into())
+}
+
+/// Generates a proof of knowledge of `x` with `y = x*G` that the Python side accepts.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::SecretScalar;
+/// # use zk_proof::{compat, generate_random_number};
+/// # use k256::ProjectivePoint;
+/// let x = SecretScalar::random(&mut rand::thread_rng());
+/// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+/// let dlog_proof = compat::prove("sid", 1, &x, y);
+/// assert!(compat::verify(&dlog_proof, "sid", 1, y).is_ok());
+/// ```
+pub fn prove(sid: &str, pid: u32, x: &SecretScalar, y: ProjectivePoint) -> DLogProof {
+    prove_with_rng(&mut rand::thread_rng(), sid, pid, x, y)
+}
+
+/// Generates a proof like [`prove`] with the nonce drawn from `rng`.
+pub fn prove_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    sid: &str,
+    pid: u32,
+    x: &SecretScalar,
+    y: ProjectivePoint,
+) -> DLogProof {
+    prove_with_base_and_rng(rng, sid, pid, x, y, ProjectivePoint::GENERATOR)
+}
+
+/// Generates a proof of knowledge of `x` with `y = x*base_point`, mirroring the Python
+/// `DLogProof.prove(sid, pid, &x, y, base_point)`.
+pub fn prove_with_base(
+    sid: &str,
+    pid: u32,
+    x: &SecretScalar,
+    y: ProjectivePoint,
+    base_point: ProjectivePoint,
+) -> DLogProof {
+    prove_with_base_and_rng(&mut rand::thread_rng(), sid, pid, x, y, base_point)
+}
+
+/// Generates a proof like [`prove_with_base`] with the nonce drawn from `rng`.
+pub fn prove_with_base_and_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    sid: &str,
+    pid: u32,
+    x: &SecretScalar,
+    y: ProjectivePoint,
+    base_point: ProjectivePoint,
+) -> DLogProof {
+    let r = SecretScalar::random(rng);
+    DLogProof::prove_with_nonce(r, x, base_point, y, |points| hash_points(sid, pid, points))
+}
+
+/// Verifies a proof produced by [`prove`] or by the Python `DLogProof.prove`.
+pub fn verify(proof: &DLogProof, sid: &str, pid: u32, y: ProjectivePoint) -> Result<(), ZkError> {
+    verify_with_base(proof, sid, pid, y, ProjectivePoint::GENERATOR)
+}
+
+/// Verifies a proof produced by [`prove_with_base`] or by the Python `DLogProof.prove` with
+/// the same `base_point`.
+pub fn verify_with_base(
+    proof: &DLogProof,
+    sid: &str,
+    pid: u32,
+    y: ProjectivePoint,
+    base_point: ProjectivePoint,
+) -> Result<(), ZkError> {
+    proof.verify_with_challenge(base_point, y, |points| hash_points(sid, pid, points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::{
+        elliptic_curve::{group::GroupEncoding, sec1::FromEncodedPoint, PrimeField},
+        AffinePoint, EncodedPoint,
+    };
+
+    struct Vector {
+        sid: String,
+        pid: u32,
+        x: Scalar,
+        r: Scalar,
+        base: ProjectivePoint,
+        y: ProjectivePoint,
+        t: ProjectivePoint,
+        c: Scalar,
+        s: Scalar,
+    }
+
+    fn scalar(value: &serde_json::Value) -> Scalar {
+        let bytes = hex::decode(value.as_str().unwrap()).unwrap();
+        Scalar::from_repr(*k256::FieldBytes::from_slice(&bytes)).unwrap()
+    }
+
+    fn raw_point(value: &serde_json::Value) -> ProjectivePoint {
+        let mut bytes = vec![0x04];
+        bytes.extend(hex::decode(value.as_str().unwrap()).unwrap());
+        let encoded = EncodedPoint::from_bytes(bytes).unwrap();
+        AffinePoint::from_encoded_point(&encoded).unwrap().into()
+    }
+
+    fn vectors() -> Vec<Vector> {
+        let data: serde_json::Value =
+            serde_json::from_str(include_str!("../test_vectors/python_dlog.json")).unwrap();
+        data.as_array()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                let c = hex::decode(v["c"].as_str().unwrap()).unwrap();
+                Vector {
+                    sid: v["sid"].as_str().unwrap().to_owned(),
+                    pid: v["pid"].as_u64().unwrap() as u32,
+                    x: scalar(&v["x"]),
+                    r: scalar(&v["r"]),
+                    base: raw_point(&v["base"]),
+                    y: raw_point(&v["y"]),
+                    t: raw_point(&v["t"]),
+                    c: <Scalar as Reduce<U256>>::reduce_bytes(c.as_slice().into()),
+                    s: scalar(&v["s"]),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bigint_to_bytes() {
+        assert_eq!(bigint_to_bytes(0), Vec::<u8>::new());
+        assert_eq!(bigint_to_bytes(1), vec![1]);
+        assert_eq!(bigint_to_bytes(256), vec![1, 0]);
+        assert_eq!(bigint_to_bytes(u32::MAX), vec![0xff; 4]);
+    }
+
+    #[test]
+    fn test_python_hash_points() {
+        for v in vectors() {
+            assert_eq!(hash_points(&v.sid, v.pid, &[v.base, v.y, v.t]), v.c);
+        }
+    }
+
+    #[test]
+    fn test_python_prove() {
+        for v in vectors() {
+            let (r, x) = (SecretScalar::new(v.r), SecretScalar::new(v.x));
+            let proof = DLogProof::prove_with_nonce(r, &x, v.base, v.y, |points| {
+                hash_points(&v.sid, v.pid, points)
+            });
+            assert_eq!(proof.t().to_bytes(), v.t.to_bytes());
+            assert_eq!(*proof.s(), v.s);
+        }
+    }
+
+    #[test]
+    fn test_python_verify() {
+        for v in vectors() {
+            let proof = DLogProof::new(v.t, v.s);
+            assert!(verify_with_base(&proof, &v.sid, v.pid, v.y, v.base).is_ok());
+            assert_eq!(
+                verify_with_base(&proof, &v.sid, v.pid.wrapping_add(1), v.y, v.base),
+                Err(ZkError::ChallengeMismatch)
+            );
+            assert_eq!(
+                proof.verify(&v.sid, v.pid, v.y),
+                Err(ZkError::ChallengeMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let proof = prove("sid", 1, &x, y);
+        assert!(verify(&proof, "sid", 1, y).is_ok());
+        assert_eq!(verify(&proof, "abc", 1, y), Err(ZkError::ChallengeMismatch));
+        assert_eq!(proof.verify("sid", 1, y), Err(ZkError::ChallengeMismatch));
+    }
+}
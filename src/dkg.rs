@@ -0,0 +1,863 @@
+//! Distributed key generation from Feldman VSS with a commit round and complaints.
+//!
+//! `n` parties jointly generate a key pair `(x, Y = x*G)` such that every party `i` ends up with
+//! a Shamir share `x_i` of `x`, any `t` of which reconstruct it, while nobody learns `x`. Every
+//! party deals a random secret with [Feldman VSS](crate::vss), and `x` is the sum of the secrets
+//! of the qualified dealers. The protocol runs in four rounds:
+//!
+//! 1. **Commit** ([`commit`]): every party broadcasts a [`CommitMessage`] hashing its Feldman
+//!    commitment and its [`DLogProof`] of possession of the dealt secret, so that no party can
+//!    change its contribution after seeing the others'.
+//! 2. **Share** ([`Committed::share`]): every party broadcasts the opened [`ShareBroadcast`] and
+//!    sends each other party its [`ShareMessage`] privately.
+//! 3. **Complaint** ([`Shared::complain`]): every party checks the broadcasts and its shares and
+//!    broadcasts a [`Complaint`] naming the dealers whose share was missing or invalid. Dealers
+//!    answer the complaints against them with a [`Justification`] ([`Complained::justify`]) that
+//!    reveals the disputed shares.
+//! 4. **Finalize** ([`Complained::finalize`]): dealers with an invalid broadcast or an unanswered
+//!    complaint are disqualified, and every party sums the shares of the qualified dealers into
+//!    its [`KeyShare`].
+//!
+//! This is the Joint-Feldman DKG with a hash commitment round, not the Pedersen-VSS variant of
+//! Gennaro et al. The commit round keeps contributions from depending on each other, but a party
+//! that sees the opened broadcasts of round 2 before sending its own can still withhold its
+//! opening or provoke its disqualification, and so choose between two joint keys. The public key
+//! is therefore not guaranteed to be uniformly distributed, which threshold Schnorr signatures
+//! such as [FROST](crate::frost) tolerate but other uses of the key may not.
+//!
+//! Every hash and proof is bound to the session id, the id of the party producing it and the
+//! round. Broadcasts must reach all parties unchanged and shares must be sent over confidential,
+//! authenticated channels; the caller is responsible for both, and for telling the parties who
+//! sent each message.
+//!
+//! ```
+//! use zk_proof::dkg;
+//!
+//! let (threshold, parties) = (2, 3);
+//! let (states, commits): (Vec<_>, Vec<_>) = (1..=parties)
+//!     .map(|pid| dkg::commit("sid", pid, threshold, parties).unwrap())
+//!     .unzip();
+//!
+//! let mut shared = Vec::new();
+//! let (mut broadcasts, mut shares) = (Vec::new(), Vec::new());
+//! for state in states {
+//!     let (state, broadcast, messages) = state.share(&commits).unwrap();
+//!     shared.push(state);
+//!     broadcasts.push(broadcast);
+//!     shares.extend(messages);
+//! }
+//!
+//! let (complained, complaints): (Vec<_>, Vec<_>) = shared
+//!     .into_iter()
+//!     .map(|state| state.complain(&broadcasts, &shares).unwrap())
+//!     .unzip();
+//! let justifications: Vec<_> = complained
+//!     .iter()
+//!     .map(|state| state.justify(&complaints))
+//!     .collect();
+//!
+//! let keys: Vec<_> = complained
+//!     .into_iter()
+//!     .map(|state| state.finalize(&complaints, &justifications).unwrap())
+//!     .collect();
+//! assert!(keys.iter().all(|key| key.public_key() == keys[0].public_key()));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use k256::{elliptic_curve::rand_core::CryptoRngCore, ProjectivePoint};
+use sha2::Sha256;
+
+use crate::{
+    transcript::{self, Transcript},
+    vss::{self, FeldmanCommitment, SecretShare},
+    DLogProof, SecretScalar, ZkError,
+};
+
+/// The first-round message hashing a party's [`ShareBroadcast`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMessage {
+    from: u32,
+    digest: [u8; 32],
+}
+
+impl CommitMessage {
+    /// Creates the commit message of the party `from`.
+    pub fn new(from: u32, digest: [u8; 32]) -> Self {
+        Self { from, digest }
+    }
+
+    /// Returns the id of the sending party.
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the hash of the sender's [`ShareBroadcast`].
+    pub fn digest(&self) -> &[u8; 32] {
+        &self.digest
+    }
+}
+
+/// The second-round broadcast of a dealer's Feldman commitment and proof of possession.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareBroadcast {
+    from: u32,
+    commitment: FeldmanCommitment,
+    proof: DLogProof,
+}
+
+impl ShareBroadcast {
+    /// Creates the broadcast of the dealer `from`.
+    pub fn new(from: u32, commitment: FeldmanCommitment, proof: DLogProof) -> Self {
+        Self {
+            from,
+            commitment,
+            proof,
+        }
+    }
+
+    /// Returns the id of the dealer.
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the dealer's commitment to its polynomial.
+    pub fn commitment(&self) -> &FeldmanCommitment {
+        &self.commitment
+    }
+
+    /// Returns the dealer's proof of knowledge of its secret.
+    pub fn proof(&self) -> &DLogProof {
+        &self.proof
+    }
+
+    /// Hashes the broadcast for its [`CommitMessage`].
+    fn digest(&self, sid: &str) -> [u8; 32] {
+        let mut transcript = round(sid, self.from, b"commit");
+        for a_j in self.commitment.coefficients() {
+            transcript.append_point(b"coefficient", a_j);
+        }
+        transcript.append_point(b"t", self.proof.t());
+        transcript.append_scalar(b"s", self.proof.s());
+        let mut digest = [0u8; 32];
+        transcript.challenge_bytes(b"digest", &mut digest);
+        digest
+    }
+
+    /// Checks that the broadcast opens `digest`, matches the threshold and proves possession.
+    fn verify(&self, sid: &str, threshold: usize, digest: &[u8; 32]) -> bool {
+        self.digest(sid) == *digest
+            && self.commitment.threshold() == threshold
+            && self
+                .proof
+                .verify_with_transcript(
+                    &mut round(sid, self.from, b"proof of possession"),
+                    self.commitment.public_key(),
+                    ProjectivePoint::GENERATOR,
+                )
+                .is_ok()
+    }
+}
+
+/// The second-round share a dealer sends privately to one party.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareMessage {
+    from: u32,
+    share: SecretShare,
+}
+
+impl ShareMessage {
+    /// Creates the message carrying the share of the dealer `from` for the party
+    /// `share.index()`.
+    pub fn new(from: u32, share: SecretShare) -> Self {
+        Self { from, share }
+    }
+
+    /// Returns the id of the dealer.
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the id of the receiving party.
+    pub fn to(&self) -> u32 {
+        self.share.index()
+    }
+
+    /// Returns the share.
+    pub fn share(&self) -> &SecretShare {
+        &self.share
+    }
+}
+
+/// The third-round broadcast naming the dealers whose share the sender did not accept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Complaint {
+    from: u32,
+    accused: Vec<u32>,
+}
+
+impl Complaint {
+    /// Creates the complaint of the party `from` against the dealers `accused`.
+    pub fn new(from: u32, accused: Vec<u32>) -> Self {
+        Self { from, accused }
+    }
+
+    /// Returns the id of the complaining party.
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the ids of the accused dealers.
+    pub fn accused(&self) -> &[u32] {
+        &self.accused
+    }
+}
+
+/// A dealer's broadcast answer to the complaints against it, revealing the disputed shares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Justification {
+    from: u32,
+    shares: Vec<SecretShare>,
+}
+
+impl Justification {
+    /// Creates the justification of the dealer `from`.
+    pub fn new(from: u32, shares: Vec<SecretShare>) -> Self {
+        Self { from, shares }
+    }
+
+    /// Returns the id of the dealer.
+    pub fn from(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the revealed shares of the complaining parties.
+    pub fn shares(&self) -> &[SecretShare] {
+        &self.shares
+    }
+}
+
+/// A party's result of the key generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    threshold: usize,
+    share: SecretShare,
+    public_key: ProjectivePoint,
+    verification_shares: Vec<ProjectivePoint>,
+    qualified: Vec<u32>,
+}
+
+impl KeyShare {
+    /// Returns the number of shares `t` needed to reconstruct the joint secret.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the party's share `x_i` of the joint secret.
+    pub fn share(&self) -> &SecretShare {
+        &self.share
+    }
+
+    /// Returns the joint public key `Y = x*G`.
+    pub fn public_key(&self) -> ProjectivePoint {
+        self.public_key
+    }
+
+    /// Returns the public share `Y_i = x_i*G` of the party `pid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pid` is not between 1 and the number of parties.
+    pub fn verification_share(&self, pid: u32) -> ProjectivePoint {
+        self.verification_shares[pid as usize - 1]
+    }
+
+    /// Returns the ids of the dealers whose secrets make up the joint secret.
+    pub fn qualified(&self) -> &[u32] {
+        &self.qualified
+    }
+}
+
+/// The public parameters of a key generation, shared by all states.
+#[derive(Debug, Clone)]
+struct Session {
+    sid: String,
+    pid: u32,
+    threshold: usize,
+    parties: u32,
+}
+
+impl Session {
+    /// Collects one message per party other than this one, keyed by sender.
+    fn collect<'a, T>(
+        &self,
+        messages: impl IntoIterator<Item = &'a T>,
+        from: impl Fn(&T) -> u32,
+    ) -> Result<BTreeMap<u32, &'a T>, ZkError> {
+        let mut collected = BTreeMap::new();
+        for message in messages {
+            let sender = from(message);
+            check_pid(sender, self.parties)?;
+            if sender != self.pid && collected.insert(sender, message).is_some() {
+                return Err(ZkError::InvalidInput(format!(
+                    "more than one message from party {}",
+                    sender
+                )));
+            }
+        }
+        Ok(collected)
+    }
+
+    fn others(&self) -> impl Iterator<Item = u32> + '_ {
+        (1..=self.parties).filter(move |&pid| pid != self.pid)
+    }
+}
+
+/// The state of a party after the commit round.
+#[derive(Debug)]
+pub struct Committed {
+    session: Session,
+    broadcast: ShareBroadcast,
+    shares: Vec<SecretShare>,
+}
+
+/// The state of a party after the share round.
+#[derive(Debug)]
+pub struct Shared {
+    session: Session,
+    digests: BTreeMap<u32, [u8; 32]>,
+    broadcast: ShareBroadcast,
+    shares: Vec<SecretShare>,
+}
+
+/// The state of a party after the complaint round.
+#[derive(Debug)]
+pub struct Complained {
+    session: Session,
+    commitments: BTreeMap<u32, FeldmanCommitment>,
+    shares: Vec<SecretShare>,
+    received: BTreeMap<u32, SecretShare>,
+    complaint: Complaint,
+}
+
+/// Starts the key generation for the party `pid` by dealing a random secret.
+///
+/// # Arguments
+///
+/// * `sid` - The session id, which must be unique to this key generation.
+/// * `pid` - The id of the party, between 1 and `parties`.
+/// * `threshold` - The number of shares `t` needed to reconstruct the joint secret.
+/// * `parties` - The number of parties `n`.
+///
+/// # Returns
+///
+/// The party's state and the [`CommitMessage`] to broadcast.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] unless `1 <= threshold <= parties` and
+/// `1 <= pid <= parties`.
+pub fn commit(
+    sid: &str,
+    pid: u32,
+    threshold: usize,
+    parties: u32,
+) -> Result<(Committed, CommitMessage), ZkError> {
+    commit_with_rng(&mut rand::thread_rng(), sid, pid, threshold, parties)
+}
+
+/// Starts the key generation like [`commit`] with the dealt secret, the polynomial and the proof
+/// nonce drawn from `rng`.
+pub fn commit_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    sid: &str,
+    pid: u32,
+    threshold: usize,
+    parties: u32,
+) -> Result<(Committed, CommitMessage), ZkError> {
+    check_pid(pid, parties)?;
+    let secret = SecretScalar::random(&mut *rng);
+    let (commitment, shares) = vss::deal(rng, &secret, threshold, parties)?;
+    let proof = DLogProof::prove_with_transcript(
+        rng,
+        &mut round(sid, pid, b"proof of possession"),
+        &secret,
+        commitment.public_key(),
+        ProjectivePoint::GENERATOR,
+    );
+    let broadcast = ShareBroadcast::new(pid, commitment, proof);
+    let message = CommitMessage::new(pid, broadcast.digest(sid));
+    let session = Session {
+        sid: sid.to_string(),
+        pid,
+        threshold,
+        parties,
+    };
+    let state = Committed {
+        session,
+        broadcast,
+        shares,
+    };
+    Ok((state, message))
+}
+
+impl Committed {
+    /// Runs the share round after receiving the [`CommitMessage`]s of the other parties.
+    ///
+    /// `commits` may include the party's own message, which is ignored. Parties without a
+    /// commit message are disqualified in [`Complained::finalize`].
+    ///
+    /// # Returns
+    ///
+    /// The party's state, the [`ShareBroadcast`] to broadcast and a [`ShareMessage`] to send
+    /// privately to every other party.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if a message comes from an unknown party or a party
+    /// sent more than one.
+    pub fn share(
+        self,
+        commits: &[CommitMessage],
+    ) -> Result<(Shared, ShareBroadcast, Vec<ShareMessage>), ZkError> {
+        let digests = self
+            .session
+            .collect(commits, CommitMessage::from)?
+            .into_iter()
+            .map(|(pid, message)| (pid, message.digest))
+            .collect();
+        let messages = self
+            .shares
+            .iter()
+            .filter(|share| share.index() != self.session.pid)
+            .map(|share| ShareMessage::new(self.session.pid, share.clone()))
+            .collect();
+        let broadcast = self.broadcast.clone();
+        let state = Shared {
+            session: self.session,
+            digests,
+            broadcast: self.broadcast,
+            shares: self.shares,
+        };
+        Ok((state, broadcast, messages))
+    }
+}
+
+impl Shared {
+    /// Runs the complaint round after receiving the [`ShareBroadcast`]s of the other parties
+    /// and the [`ShareMessage`]s addressed to this party.
+    ///
+    /// Broadcasts that do not open their commit message, have the wrong threshold or an invalid
+    /// proof of possession disqualify their dealer, which every party detects alike. Shares
+    /// that are missing or do not match their dealer's commitment are answered with a
+    /// complaint. `broadcasts` may include the party's own broadcast and `shares` messages
+    /// addressed to other parties, which are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The party's state and the [`Complaint`] to broadcast, which names no dealer if all
+    /// shares were valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if a message comes from an unknown party or a party
+    /// sent more than one.
+    pub fn complain(
+        self,
+        broadcasts: &[ShareBroadcast],
+        shares: &[ShareMessage],
+    ) -> Result<(Complained, Complaint), ZkError> {
+        let session = &self.session;
+        let broadcasts = session.collect(broadcasts, ShareBroadcast::from)?;
+        let shares = session.collect(
+            shares.iter().filter(|message| message.to() == session.pid),
+            ShareMessage::from,
+        )?;
+
+        let mut commitments = BTreeMap::new();
+        commitments.insert(session.pid, self.broadcast.commitment.clone());
+        let mut received = BTreeMap::new();
+        let mut accused = Vec::new();
+        for dealer in session.others() {
+            let (Some(digest), Some(broadcast)) =
+                (self.digests.get(&dealer), broadcasts.get(&dealer))
+            else {
+                continue;
+            };
+            if !broadcast.verify(&session.sid, session.threshold, digest) {
+                continue;
+            }
+            commitments.insert(dealer, broadcast.commitment.clone());
+            match shares.get(&dealer) {
+                Some(message) if broadcast.commitment.verify_share(&message.share).is_ok() => {
+                    received.insert(dealer, message.share.clone());
+                }
+                _ => accused.push(dealer),
+            }
+        }
+
+        let complaint = Complaint::new(session.pid, accused);
+        let state = Complained {
+            session: self.session,
+            commitments,
+            shares: self.shares,
+            received,
+            complaint: complaint.clone(),
+        };
+        Ok((state, complaint))
+    }
+}
+
+impl Complained {
+    /// Answers the complaints against this party by revealing the shares of the complaining
+    /// parties, to be broadcast.
+    ///
+    /// Complaints from unknown parties are ignored.
+    pub fn justify(&self, complaints: &[Complaint]) -> Justification {
+        let shares = complaints
+            .iter()
+            .filter(|complaint| complaint.accused.contains(&self.session.pid))
+            .filter_map(|complaint| {
+                self.shares
+                    .iter()
+                    .find(|share| share.index() == complaint.from)
+                    .cloned()
+            })
+            .collect();
+        Justification::new(self.session.pid, shares)
+    }
+
+    /// Finishes the key generation after receiving the [`Complaint`]s and [`Justification`]s of
+    /// the other parties.
+    ///
+    /// `complaints` and `justifications` may include the party's own messages, which are
+    /// ignored.
+    ///
+    /// A dealer is disqualified if its broadcast was invalid or any complaint against it is not
+    /// answered with a share matching its commitment. A party whose own complaint is answered
+    /// uses the revealed share. The key share is the sum of the shares of the qualified
+    /// dealers, and the joint public key the sum of their `A_0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if a message comes from an unknown party or a party
+    /// sent more than one.
+    pub fn finalize(
+        mut self,
+        complaints: &[Complaint],
+        justifications: &[Justification],
+    ) -> Result<KeyShare, ZkError> {
+        let session = &self.session;
+        let complaints = session.collect(complaints, Complaint::from)?;
+        let justifications = session.collect(justifications, Justification::from)?;
+
+        let mut disqualified = BTreeSet::new();
+        let own = std::iter::once((session.pid, &self.complaint));
+        for (complainer, complaint) in complaints.into_iter().chain(own) {
+            for &dealer in &complaint.accused {
+                let Some(commitment) = self.commitments.get(&dealer) else {
+                    continue;
+                };
+                if dealer == session.pid {
+                    // The party answers with its own shares, which match its commitment.
+                    continue;
+                }
+                let revealed = justifications.get(&dealer).and_then(|justification| {
+                    justification
+                        .shares
+                        .iter()
+                        .find(|share| share.index() == complainer)
+                });
+                match revealed {
+                    Some(share) if commitment.verify_share(share).is_ok() => {
+                        if complainer == session.pid {
+                            self.received.insert(dealer, share.clone());
+                        }
+                    }
+                    _ => {
+                        disqualified.insert(dealer);
+                    }
+                }
+            }
+        }
+        self.commitments
+            .retain(|dealer, _| !disqualified.contains(dealer));
+
+        let own = self
+            .shares
+            .iter()
+            .find(|share| share.index() == session.pid)
+            .expect("the dealer holds a share for itself");
+        let mut x = *own.value().expose_secret();
+        for dealer in self
+            .commitments
+            .keys()
+            .filter(|&&dealer| dealer != session.pid)
+        {
+            let share = self
+                .received
+                .get(dealer)
+                .expect("qualified dealers have a valid share for every party");
+            x += share.value().expose_secret();
+        }
+        let share = SecretShare::new(session.pid, SecretScalar::new(x));
+        let public_key = self
+            .commitments
+            .values()
+            .fold(ProjectivePoint::IDENTITY, |sum, commitment| {
+                sum + commitment.public_key()
+            });
+        let verification_shares = (1..=session.parties)
+            .map(|pid| {
+                self.commitments
+                    .values()
+                    .fold(ProjectivePoint::IDENTITY, |sum, commitment| {
+                        sum + commitment.share_point(pid)
+                    })
+            })
+            .collect();
+        Ok(KeyShare {
+            threshold: session.threshold,
+            share,
+            public_key,
+            verification_shares,
+            qualified: self.commitments.keys().copied().collect(),
+        })
+    }
+}
+
+/// Starts a transcript bound to the session, the party and the round.
+fn round(sid: &str, pid: u32, round: &[u8]) -> Transcript {
+    let mut transcript = transcript::session::<Sha256>(sid, pid);
+    transcript.append_message(b"round", round);
+    transcript
+}
+
+fn check_pid(pid: u32, parties: u32) -> Result<(), ZkError> {
+    if pid == 0 || pid > parties {
+        return Err(ZkError::InvalidInput(format!(
+            "party id {} is not between 1 and {}",
+            pid, parties
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::Scalar;
+
+    /// The messages of one run, which tests tamper with between rounds.
+    struct Run {
+        states: Vec<Committed>,
+        commits: Vec<CommitMessage>,
+    }
+
+    fn start(threshold: usize, parties: u32) -> Run {
+        let (states, commits) = (1..=parties)
+            .map(|pid| commit("sid", pid, threshold, parties).unwrap())
+            .unzip();
+        Run { states, commits }
+    }
+
+    fn share_round(
+        states: Vec<Committed>,
+        commits: &[CommitMessage],
+    ) -> (Vec<Shared>, Vec<ShareBroadcast>, Vec<ShareMessage>) {
+        let mut shared = Vec::new();
+        let (mut broadcasts, mut shares) = (Vec::new(), Vec::new());
+        for state in states {
+            let (state, broadcast, messages) = state.share(commits).unwrap();
+            shared.push(state);
+            broadcasts.push(broadcast);
+            shares.extend(messages);
+        }
+        (shared, broadcasts, shares)
+    }
+
+    fn finish(
+        shared: Vec<Shared>,
+        broadcasts: &[ShareBroadcast],
+        shares: &[ShareMessage],
+    ) -> (Vec<KeyShare>, Vec<Complaint>) {
+        let (complained, complaints): (Vec<_>, Vec<_>) = shared
+            .into_iter()
+            .map(|state| state.complain(broadcasts, shares).unwrap())
+            .unzip();
+        let justifications: Vec<_> = complained
+            .iter()
+            .map(|state| state.justify(&complaints))
+            .collect();
+        let keys = complained
+            .into_iter()
+            .map(|state| state.finalize(&complaints, &justifications).unwrap())
+            .collect();
+        (keys, complaints)
+    }
+
+    /// Checks that the key shares are consistent and any `t` of them reconstruct the secret
+    /// behind the joint public key.
+    fn check_keys(keys: &[KeyShare], qualified: &[u32]) {
+        let public_key = keys[0].public_key();
+        for key in keys {
+            assert_eq!(key.public_key(), public_key);
+            assert_eq!(key.qualified(), qualified);
+            assert_eq!(
+                key.verification_share(key.share().index()),
+                ProjectivePoint::GENERATOR * key.share().value().expose_secret()
+            );
+        }
+        let threshold = keys[0].threshold();
+        let shares: Vec<_> = keys.iter().map(|key| key.share().clone()).collect();
+        for start in 0..=shares.len() - threshold {
+            let x = vss::reconstruct(&shares[start..start + threshold]).unwrap();
+            assert_eq!(ProjectivePoint::GENERATOR * x.expose_secret(), public_key);
+        }
+    }
+
+    #[test]
+    fn test_dkg() {
+        for (threshold, parties) in [(1, 1), (1, 2), (2, 3), (3, 3), (3, 5)] {
+            let run = start(threshold, parties);
+            let (shared, broadcasts, shares) = share_round(run.states, &run.commits);
+            let (keys, complaints) = finish(shared, &broadcasts, &shares);
+            assert!(complaints
+                .iter()
+                .all(|complaint| complaint.accused().is_empty()));
+            let all: Vec<_> = (1..=parties).collect();
+            check_keys(&keys, &all);
+        }
+    }
+
+    #[test]
+    fn test_complaint_answered() {
+        // Dealer 1 sends party 2 a bad share but reveals a valid one when accused.
+        let run = start(2, 3);
+        let (shared, broadcasts, mut shares) = share_round(run.states, &run.commits);
+        let message = shares
+            .iter_mut()
+            .find(|message| message.from() == 1 && message.to() == 2)
+            .unwrap();
+        let bad = SecretScalar::new(message.share().value().expose_secret() + Scalar::ONE);
+        message.share = SecretShare::new(2, bad);
+
+        let (keys, complaints) = finish(shared, &broadcasts, &shares);
+        assert_eq!(complaints[1].accused(), [1]);
+        check_keys(&keys, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_complaint_unanswered() {
+        // Dealer 3 withholds party 1's share and does not justify.
+        let run = start(2, 3);
+        let (shared, broadcasts, mut shares) = share_round(run.states, &run.commits);
+        shares.retain(|message| !(message.from() == 3 && message.to() == 1));
+
+        let (complained, complaints): (Vec<_>, Vec<_>) = shared
+            .into_iter()
+            .map(|state| state.complain(&broadcasts, &shares).unwrap())
+            .unzip();
+        assert_eq!(complaints[0].accused(), [3]);
+        let justifications: Vec<_> = complained[..2]
+            .iter()
+            .map(|state| state.justify(&complaints))
+            .collect();
+        let keys: Vec<_> = complained
+            .into_iter()
+            .take(2)
+            .map(|state| state.finalize(&complaints, &justifications).unwrap())
+            .collect();
+        check_keys(&keys, &[1, 2]);
+    }
+
+    #[test]
+    fn test_false_complaint() {
+        // Party 2 accuses the honest dealer 1, who keeps qualifying by revealing the share.
+        let run = start(2, 3);
+        let (shared, broadcasts, shares) = share_round(run.states, &run.commits);
+        let (complained, mut complaints): (Vec<_>, Vec<_>) = shared
+            .into_iter()
+            .map(|state| state.complain(&broadcasts, &shares).unwrap())
+            .unzip();
+        complaints[1] = Complaint::new(2, vec![1]);
+        let justifications: Vec<_> = complained
+            .iter()
+            .map(|state| state.justify(&complaints))
+            .collect();
+        assert_eq!(justifications[0].shares().len(), 1);
+        let keys: Vec<_> = complained
+            .into_iter()
+            .map(|state| state.finalize(&complaints, &justifications).unwrap())
+            .collect();
+        check_keys(&keys, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_invalid_broadcast_disqualified() {
+        // Dealer 2 broadcasts a commitment other than the one it committed to, and dealer 3 a
+        // proof of possession that is not bound to the round.
+        let mut run = start(2, 4);
+        let (commitment, proof, _) =
+            vss::share("sid", 3, &SecretScalar::new(Scalar::ONE), 2, 4).unwrap();
+        let unbound = ShareBroadcast::new(3, commitment, proof);
+        run.commits[2] = CommitMessage::new(3, unbound.digest("sid"));
+
+        let (shared, mut broadcasts, shares) = share_round(run.states, &run.commits);
+        let (other, _, _) = vss::share("sid", 2, &SecretScalar::new(Scalar::ONE), 2, 4).unwrap();
+        broadcasts[1].commitment = other;
+        broadcasts[2] = unbound;
+
+        let (keys, complaints) = finish(shared, &broadcasts, &shares);
+        assert!(complaints
+            .iter()
+            .all(|complaint| complaint.accused().is_empty()));
+        // Dealers 2 and 3 still count themselves.
+        check_keys(&[keys[0].clone(), keys[3].clone()], &[1, 4]);
+    }
+
+    #[test]
+    fn test_missing_commit_disqualified() {
+        let run = start(2, 3);
+        let commits = &run.commits[..2];
+        let (shared, broadcasts, shares) = share_round(run.states, commits);
+        let (keys, _) = finish(shared, &broadcasts, &shares);
+        // Party 3 did not see its own commit missing, so only parties 1 and 2 agree.
+        check_keys(&keys[..2], &[1, 2]);
+    }
+
+    #[test]
+    fn test_digest_bound_to_session() {
+        let run = start(2, 3);
+        let broadcast = &run.states[0].broadcast;
+        assert_eq!(broadcast.digest("sid"), *run.commits[0].digest());
+        assert_ne!(broadcast.digest("abc"), *run.commits[0].digest());
+        let moved = ShareBroadcast::new(2, broadcast.commitment.clone(), broadcast.proof);
+        assert_ne!(moved.digest("sid"), *run.commits[0].digest());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for (pid, threshold, parties) in [(0, 1, 3), (4, 1, 3), (1, 0, 3), (1, 4, 3)] {
+            assert!(matches!(
+                commit("sid", pid, threshold, parties),
+                Err(ZkError::InvalidInput(_))
+            ));
+        }
+
+        let run = start(2, 3);
+        let mut states = run.states.into_iter();
+        let state = states.next().unwrap();
+        let duplicated = [run.commits[1].clone(), run.commits[1].clone()];
+        assert!(matches!(
+            state.share(&duplicated),
+            Err(ZkError::InvalidInput(_))
+        ));
+        let state = states.next().unwrap();
+        let unknown = [CommitMessage::new(4, [0; 32])];
+        assert!(matches!(
+            state.share(&unknown),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+use k256::{
+    elliptic_curve::{group::Group, rand_core::CryptoRngCore},
+    ProjectivePoint,
+};
+use sha2::Sha256;
+
+use crate::{
+    hash::ZkDigest,
+    sigma::{ChaumPedersen, SigmaProof},
+    transcript::{self, Transcript},
+    SecretScalar, ZkError, ZkGroup,
+};
+
+/// Non-interactive Chaum-Pedersen proof that `log_G(y) == log_H(z)` with a Fiat-Shamir
+/// transformation of the [`ChaumPedersen`] sigma protocol.
+///
+/// Like [`DLogProof`](crate::DLogProof), the proof is generic over the prime-order group `P` and
+/// defaults to secp256k1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLEqProof<P: Group = ProjectivePoint> {
+    t1: P,
+    t2: P,
+    s: P::Scalar,
+}
+
+impl<P: ZkGroup> DLEqProof<P> {
+    /// Creates a proof from its commitments `t1 = r*G`, `t2 = r*H` and response `s`.
+    pub fn new(t1: P, t2: P, s: P::Scalar) -> Self {
+        Self { t1, t2, s }
+    }
+
+    /// Returns the commitment `t1` relative to `G`.
+    pub fn t1(&self) -> &P {
+        &self.t1
+    }
+
+    /// Returns the commitment `t2` relative to `H`.
+    pub fn t2(&self) -> &P {
+        &self.t2
+    }
+
+    /// Returns the response scalar `s`.
+    pub fn s(&self) -> &P::Scalar {
+        &self.s
+    }
+
+    /// Generates a proof that `y = x*G` and `z = x*H` share the discrete logarithm `x`.
+    ///
+    /// The prover generates a random number `r`, computes `t1 = r*G`, `t2 = r*H` and
+    /// `c = H(sid, pid, G, H, y, z, t1, t2)` over a SHA-256 transcript, and then computes
+    /// `s = r + c*x` and returns the proof `(t1, t2, s)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `x` - The secret number.
+    /// * `h` - The second base point.
+    /// * `y` - The point `x*G`.
+    /// * `z` - The point `x*H`.
+    ///
+    /// # Returns
+    ///
+    /// A `DLEqProof` struct containing the `t1`, `t2` and `s` values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::SecretScalar;
+    /// # use zk_proof::DLEqProof;
+    /// # use zk_proof::generate_random_number;
+    /// # use k256::ProjectivePoint;
+    /// let sid = "sid";
+    /// let pid = 1;
+    /// let h = ProjectivePoint::GENERATOR * generate_random_number();
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let z = h * x.expose_secret();
+    /// let dleq_proof = DLEqProof::prove(sid, pid, &x, h, y, z);
+    /// assert!(dleq_proof.verify(sid, pid, h, y, z).is_ok());
+    /// ```
+    pub fn prove(sid: &str, pid: u32, x: &SecretScalar<P::Scalar>, h: P, y: P, z: P) -> Self {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, x, h, y, z)
+    }
+
+    /// Generates a proof like [`DLEqProof::prove`] with the nonce `r` drawn from `rng`.
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        h: P,
+        y: P,
+        z: P,
+    ) -> Self {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, x, h, y, z)
+    }
+
+    /// Generates a proof like [`DLEqProof::prove`] with the challenge derived from
+    /// `transcript`, which may hash with any [`ZkDigest`] and already hold the caller's context.
+    ///
+    /// The verifier must pass a transcript in the same state to
+    /// [`DLEqProof::verify_with_transcript`].
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        x: &SecretScalar<P::Scalar>,
+        h: P,
+        y: P,
+        z: P,
+    ) -> Self {
+        let proof = SigmaProof::prove(rng, transcript, &Self::statement(h, y, z), x)
+            .expect("a Chaum-Pedersen commitment does not fail");
+        let (t1, t2) = *proof.commitment();
+        Self::new(t1, t2, *proof.response())
+    }
+
+    /// Verifies that `s*G == t1 + c*y` and `s*H == t2 + c*z`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `h` - The second base point.
+    /// * `y` - The point claimed to be `x*G`.
+    /// * `z` - The point claimed to be `x*H`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if any of `h`, `y`, `z`, `t1` or `t2` is the point at
+    /// infinity and [`ZkError::ChallengeMismatch`] unless `y` and `z` share their discrete
+    /// logarithm.
+    pub fn verify(&self, sid: &str, pid: u32, h: P, y: P, z: P) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, h, y, z)
+    }
+
+    /// Verifies a proof generated by [`DLEqProof::prove_with_transcript`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`DLEqProof::verify`].
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        h: P,
+        y: P,
+        z: P,
+    ) -> Result<(), ZkError> {
+        SigmaProof::<ChaumPedersen<P>>::new((self.t1, self.t2), self.s)
+            .verify(transcript, &Self::statement(h, y, z))
+    }
+
+    fn statement(h: P, y: P, z: P) -> ChaumPedersen<P> {
+        ChaumPedersen::new(P::generator(), h, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_random_number;
+    use k256::Scalar;
+
+    fn statement() -> (
+        SecretScalar,
+        ProjectivePoint,
+        ProjectivePoint,
+        ProjectivePoint,
+    ) {
+        let h = ProjectivePoint::GENERATOR * generate_random_number();
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let (y, z) = (
+            ProjectivePoint::GENERATOR * x.expose_secret(),
+            h * x.expose_secret(),
+        );
+        (x, h, y, z)
+    }
+
+    #[test]
+    fn test_verify() {
+        let (x, h, y, z) = statement();
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        assert!(dleq_proof.verify("sid", 1, h, y, z).is_ok());
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_sid_or_pid() {
+        let (x, h, y, z) = statement();
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        assert_eq!(
+            dleq_proof.verify("abc", 1, h, y, z),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            dleq_proof.verify("sid", 2, h, y, z),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_unequal_logs() {
+        let (x, h, y, _) = statement();
+        let z = h * (x.expose_secret() + Scalar::ONE);
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        assert_eq!(
+            dleq_proof.verify("sid", 1, h, y, z),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_other_base() {
+        let (x, h, y, z) = statement();
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        let other = ProjectivePoint::GENERATOR * generate_random_number();
+        assert_eq!(
+            dleq_proof.verify("sid", 1, other, y, other * x.expose_secret()),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_identity() {
+        let identity = ProjectivePoint::IDENTITY;
+        let trivial = DLEqProof::new(identity, identity, Scalar::ZERO);
+        assert_eq!(
+            trivial.verify("sid", 1, identity, identity, identity),
+            Err(ZkError::IdentityPoint)
+        );
+
+        let (x, h, y, z) = statement();
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        assert_eq!(
+            dleq_proof.verify("sid", 1, identity, y, identity),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_transcript_digest() {
+        let (x, h, y, z) = statement();
+        let transcript = Transcript::<sha3::Sha3_256>::with_digest(b"test");
+        let dleq_proof = DLEqProof::prove_with_transcript(
+            &mut rand::thread_rng(),
+            &mut transcript.clone(),
+            &x,
+            h,
+            y,
+            z,
+        );
+        assert!(dleq_proof
+            .verify_with_transcript(&mut transcript.clone(), h, y, z)
+            .is_ok());
+        assert_eq!(
+            dleq_proof.verify_with_transcript(&mut Transcript::new(b"test"), h, y, z),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_p256() {
+        use p256::{ProjectivePoint, Scalar};
+
+        let mut rng = rand::thread_rng();
+        let h =
+            ProjectivePoint::GENERATOR * SecretScalar::<Scalar>::random(&mut rng).expose_secret();
+        let x = SecretScalar::<Scalar>::random(&mut rng);
+        let (y, z) = (
+            ProjectivePoint::GENERATOR * x.expose_secret(),
+            h * x.expose_secret(),
+        );
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        assert!(dleq_proof.verify("sid", 1, h, y, z).is_ok());
+        assert_eq!(
+            dleq_proof.verify("sid", 1, h, y, y),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+}
@@ -0,0 +1,1114 @@
+use k256::{
+    elliptic_curve::{group::Group, rand_core::CryptoRngCore, zeroize::Zeroize, Field, PrimeField},
+    ProjectivePoint, Scalar,
+};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+use crate::{
+    encoding::{
+        decimal_to_bytes, decode_scalar, hex_to_bytes, Bytes, Encoded, EncodedProof, Encoding,
+        PairVisitor,
+    },
+    group::{ensure_non_identity, point_len, reduce_be_bytes, scalar_len},
+    hash::ZkDigest,
+    msm::msm,
+    sigma::{Schnorr, SigmaProof, SigmaProtocol},
+    transcript::{self, Transcript},
+    SecretScalar, ZkError, ZkGroup,
+};
+
+/// The name binding [`DLogProof`] challenges to this kind of proof.
+pub(crate) const PROOF: &[u8] = b"DLogProof";
+
+/// Generates a uniformly random scalar using the thread-local RNG.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::generate_random_number;
+/// let x = generate_random_number();
+/// ```
+pub fn generate_random_number() -> Scalar {
+    generate_random_number_with_rng(&mut rand::thread_rng())
+}
+
+/// Generates a uniformly random scalar using the given cryptographically secure RNG.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::generate_random_number_with_rng;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let x = generate_random_number_with_rng(&mut rng);
+/// assert_eq!(x, generate_random_number_with_rng(&mut StdRng::seed_from_u64(42)));
+/// ```
+pub fn generate_random_number_with_rng(rng: &mut (impl CryptoRngCore + ?Sized)) -> Scalar {
+    Scalar::random(rng)
+}
+
+/// Non-interactive Schnorr ZK DLOG proof with a Fiat-Shamir transformation of the [`Schnorr`]
+/// sigma protocol.
+///
+/// The proof is generic over the prime-order group `P` (see [`ZkGroup`]) and defaults to
+/// secp256k1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLogProof<P: Group = ProjectivePoint> {
+    t: P,
+    s: P::Scalar,
+}
+
+impl<P: ZkGroup> DLogProof<P> {
+    /// Creates a proof from its commitment `t` and response `s`.
+    pub fn new(t: P, s: P::Scalar) -> Self {
+        Self { t, s }
+    }
+
+    /// Returns the commitment point `t`.
+    pub fn t(&self) -> &P {
+        &self.t
+    }
+
+    /// Returns the response scalar `s`.
+    pub fn s(&self) -> &P::Scalar {
+        &self.s
+    }
+
+    /// Computes a hash of the given session id, point id, and points.
+    ///
+    /// This function takes a session id, a point id, and a vector of points, and derives the
+    /// challenge from a SHA-256 [`Transcript`], appending each input under its own label.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the point.
+    /// * `points` - The points to be hashed.
+    ///
+    /// # Returns
+    ///
+    /// A `Scalar` representing the hash of the inputs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use k256::ProjectivePoint;
+    /// let sid = "sid";
+    /// let pid = 1;
+    /// let points = vec![ProjectivePoint::GENERATOR; 3];
+    /// let hash = DLogProof::hash_points(sid, pid, &points);
+    /// println!("{:?}", hash);
+    /// ```
+    pub fn hash_points(sid: &str, pid: u32, points: &[P]) -> P::Scalar {
+        transcript::challenge(&mut transcript::session::<Sha256>(sid, pid), PROOF, points)
+    }
+
+    /// Generates a proof that the prover knows the discrete logarithm of `y`.
+    ///
+    /// The prover generates a random number `r`, computes `t = r*G` and `c = H(sid, pid, G, y, t)`,
+    /// and then computes `s = r + c*x` and returns the proof `(t, s)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `x` - The secret number.
+    /// * `y` - The point that we want to prove that we know the discrete logarithm of.
+    ///
+    /// # Returns
+    ///
+    /// A `DLogProof` struct containing the `t` and `s` values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let sid = "sid";
+    /// let pid = 1;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+    /// ```
+    pub fn prove(sid: &str, pid: u32, x: &SecretScalar<P::Scalar>, y: P) -> Self {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, x, y)
+    }
+
+    /// Generates a proof like [`DLogProof::prove`] with the nonce `r` drawn from `rng`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove_with_rng(&mut StdRng::seed_from_u64(7), "sid", 1, &x, y);
+    /// assert!(dlog_proof.verify("sid", 1, y).is_ok());
+    /// ```
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+    ) -> Self {
+        Self::prove_with_base_and_rng(rng, sid, pid, x, y, P::generator())
+    }
+
+    /// Generates a proof that the prover knows the discrete logarithm of `y` relative to
+    /// `base_point`.
+    ///
+    /// Same as [`DLogProof::prove`] with `G` replaced by `base_point`, which is bound into the
+    /// challenge `c = H(sid, pid, base_point, y, t)`. A proof for one base point therefore never
+    /// verifies against another.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `x` - The secret number.
+    /// * `y` - The point `x*base_point`.
+    /// * `base_point` - The generator the discrete logarithm is taken relative to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::SecretScalar;
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::generate_random_number;
+    /// # use k256::ProjectivePoint;
+    /// let sid = "sid";
+    /// let pid = 1;
+    /// let h = ProjectivePoint::GENERATOR * generate_random_number();
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = h * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove_with_base(sid, pid, &x, y, h);
+    /// assert!(dlog_proof.verify_with_base(sid, pid, y, h).is_ok());
+    /// ```
+    pub fn prove_with_base(
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+        base_point: P,
+    ) -> Self {
+        Self::prove_with_base_and_rng(&mut rand::thread_rng(), sid, pid, x, y, base_point)
+    }
+
+    /// Generates a proof like [`DLogProof::prove_with_base`] with the nonce `r` drawn from
+    /// `rng`.
+    pub fn prove_with_base_and_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+        base_point: P,
+    ) -> Self {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, x, y, base_point)
+    }
+
+    /// Generates a proof like [`DLogProof::prove`] with the nonce `r` derived deterministically
+    /// instead of drawn from the RNG.
+    ///
+    /// `r` is generated with the HMAC-DRBG of RFC 6979 keyed by `x` and a hash of the statement
+    /// `(sid, pid, G, y)`, so it never repeats for two different statements and a broken or
+    /// cloned RNG cannot leak `x`. Proving the same statement twice yields the same proof.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `x` - The secret number.
+    /// * `y` - The point that we want to prove that we know the discrete logarithm of.
+    /// * `extra` - Additional randomness mixed into `r` to hedge against fault attacks; may be
+    ///   empty for fully deterministic proofs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove_deterministic("sid", 1, &x, y, &[]);
+    /// assert_eq!(dlog_proof, DLogProof::prove_deterministic("sid", 1, &x, y, &[]));
+    /// assert!(dlog_proof.verify("sid", 1, y).is_ok());
+    /// ```
+    pub fn prove_deterministic(
+        sid: &str,
+        pid: u32,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+        extra: &[u8],
+    ) -> Self {
+        let base = P::generator();
+        let r = deterministic_nonce(sid, pid, x, &[base, y], extra);
+        Self::prove_with_nonce(r, x, base, y, |points| Self::hash_points(sid, pid, points))
+    }
+
+    /// Generates a proof that the prover knows the discrete logarithm of `y` relative to
+    /// `base_point`, deriving the challenge from `transcript`.
+    ///
+    /// The statement and the commitment `t` are appended to `transcript`, which may hash with
+    /// any [`ZkDigest`] and already hold the caller's context, such as a session id. The
+    /// verifier must pass a transcript in the same state to
+    /// [`DLogProof::verify_with_transcript`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{DLogProof, Transcript};
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// use sha2::Sha512;
+    ///
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let mut transcript = Transcript::<Sha512>::with_digest(b"my-protocol");
+    /// let dlog_proof = DLogProof::prove_with_transcript(
+    ///     &mut rand::thread_rng(),
+    ///     &mut transcript.clone(),
+    ///     &x,
+    ///     y,
+    ///     ProjectivePoint::GENERATOR,
+    /// );
+    /// assert!(dlog_proof
+    ///     .verify_with_transcript(&mut transcript, y, ProjectivePoint::GENERATOR)
+    ///     .is_ok());
+    /// ```
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        x: &SecretScalar<P::Scalar>,
+        y: P,
+        base_point: P,
+    ) -> Self {
+        let proof = SigmaProof::prove(rng, transcript, &Schnorr::new(base_point, y), x)
+            .expect("a Schnorr commitment does not fail");
+        Self::new(*proof.commitment(), *proof.response())
+    }
+
+    /// Computes `t = r*base` and `s = r + c*x` where `c` is derived by `challenge` from
+    /// `[base, y, t]`.
+    pub(crate) fn prove_with_nonce(
+        r: SecretScalar<P::Scalar>,
+        x: &SecretScalar<P::Scalar>,
+        base: P,
+        y: P,
+        challenge: impl FnOnce(&[P]) -> P::Scalar,
+    ) -> Self {
+        let t = base * r.expose_secret();
+        let c = challenge(&[base, y, t]);
+        Self::new(t, Schnorr::new(base, y).respond(x, r, &c))
+    }
+
+    /// Checks `s*base == t + c*y` where `c` is derived by `challenge` from `[base, y, t]`.
+    pub(crate) fn verify_with_challenge(
+        &self,
+        base: P,
+        y: P,
+        challenge: impl FnOnce(&[P]) -> P::Scalar,
+    ) -> Result<(), ZkError> {
+        let c = challenge(&[base, y, self.t]);
+        Schnorr::new(base, y).verify(&self.t, &c, &self.s)
+    }
+
+    /// Verifies that the point `t` equals `s` times the base point plus the hash of the inputs times `y`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `y` - The public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `y` or `t` is the point at infinity and
+    /// [`ZkError::ChallengeMismatch`] if the point `t` is not the correct sum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let sid = "sid";
+    /// let pid = 1;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+    /// assert!(dlog_proof.verify(sid, pid, y).is_ok());
+    /// ```
+    pub fn verify(&self, sid: &str, pid: u32, y: P) -> Result<(), ZkError> {
+        self.verify_with_base(sid, pid, y, P::generator())
+    }
+
+    /// Verifies a proof generated by [`DLogProof::prove_with_base`], i.e. that
+    /// `s*base_point == t + c*y`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `y` - The public point.
+    /// * `base_point` - The generator the proof was made for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `y`, `t` or `base_point` is the point at infinity
+    /// and [`ZkError::ChallengeMismatch`] if the proof does not hold for `base_point`.
+    pub fn verify_with_base(
+        &self,
+        sid: &str,
+        pid: u32,
+        y: P,
+        base_point: P,
+    ) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, y, base_point)
+    }
+
+    /// Verifies a proof generated by [`DLogProof::prove_with_transcript`], i.e. that
+    /// `s*base_point == t + c*y` with `c` derived from `transcript`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `y`, `t` or `base_point` is the point at infinity
+    /// and [`ZkError::ChallengeMismatch`] if the proof does not hold for `transcript`.
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        y: P,
+        base_point: P,
+    ) -> Result<(), ZkError> {
+        SigmaProof::<Schnorr<P>>::new(self.t, self.s)
+            .verify(transcript, &Schnorr::new(base_point, y))
+    }
+
+    /// Verifies many proofs at once with a single multi-scalar multiplication.
+    ///
+    /// Each proof `i` claims `s_i*G - t_i - c_i*y_i == 0`. The batch draws random weights `z_i`
+    /// and checks the random linear combination
+    /// `(sum z_i*s_i)*G - sum z_i*t_i - sum (z_i*c_i)*y_i == 0`, which holds for an invalid
+    /// proof only with negligible probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `(sid, pid, y, proof)` tuples to verify.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if every proof is valid, otherwise `Err(i)` with the index of the first invalid
+    /// proof. Proofs where `y` or `t` is the point at infinity are invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let items: Vec<_> = (1..=3)
+    ///     .map(|pid| {
+    ///         let x = SecretScalar::random(&mut rand::thread_rng());
+    ///         let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    ///         ("sid", pid, y, DLogProof::prove("sid", pid, &x, y))
+    ///     })
+    ///     .collect();
+    /// assert_eq!(DLogProof::batch_verify(&items), Ok(()));
+    /// ```
+    pub fn batch_verify(items: &[(&str, u32, P, DLogProof<P>)]) -> Result<(), usize> {
+        Self::batch_verify_with_rng(&mut rand::thread_rng(), items)
+    }
+
+    /// Verifies many proofs like [`DLogProof::batch_verify`] with the random weights drawn from
+    /// `rng`.
+    pub fn batch_verify_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        items: &[(&str, u32, P, DLogProof<P>)],
+    ) -> Result<(), usize> {
+        let mut scalars = Vec::with_capacity(2 * items.len() + 1);
+        let mut points = Vec::with_capacity(2 * items.len() + 1);
+        let mut s_sum = P::Scalar::ZERO;
+        let mut failed = false;
+        for (sid, pid, y, proof) in items {
+            // An earlier proof may fail only the combined equation, so the first invalid proof
+            // is left to the scan below.
+            if ensure_non_identity(&[*y, proof.t]).is_err() {
+                failed = true;
+                break;
+            }
+            let z = P::Scalar::random(&mut *rng);
+            let c = Self::hash_points(sid, *pid, &[P::generator(), *y, proof.t]);
+            s_sum += z * proof.s;
+            scalars.extend([-z, -(z * c)]);
+            points.extend([proof.t, *y]);
+        }
+        scalars.push(s_sum);
+        points.push(P::generator());
+
+        if !failed && bool::from(msm(&scalars, &points).is_identity()) {
+            return Ok(());
+        }
+        match items
+            .iter()
+            .position(|(sid, pid, y, proof)| proof.verify(sid, *pid, *y).is_err())
+        {
+            Some(index) => Err(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Serializes the proof into a JSON object holding the compressed `t` and big-endian `s`
+    /// bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+    /// let data = dlog_proof.to_dict();
+    /// assert_eq!(DLogProof::from_dict(&data).unwrap(), dlog_proof);
+    /// ```
+    pub fn to_dict(&self) -> serde_json::Value {
+        serde_json::json!({
+            "t": self.t.to_bytes().as_ref(),
+            "s": self.s.to_repr().as_ref(),
+        })
+    }
+
+    /// Serializes the proof into the JSON text of [`DLogProof::to_dict`].
+    pub fn to_str(&self) -> String {
+        self.to_dict().to_string()
+    }
+
+    /// Deserializes a proof from a JSON object.
+    ///
+    /// Accepts the output of [`DLogProof::to_dict`] as well as the Python `DLogProof.to_dict`:
+    ///
+    /// * `t` is either an array of bytes or a hex string holding a SEC1 compressed, SEC1
+    ///   uncompressed or raw 64-byte `x || y` point encoding.
+    /// * `s` is either an array of 32 big-endian bytes, a decimal string, a `0x`-prefixed hex
+    ///   string or a JSON integer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::MalformedPoint`] if `t` is not on the curve, [`ZkError::IdentityPoint`]
+    /// if `t` is the point at infinity,
+    /// [`ZkError::NonCanonicalScalar`] if `s` is not smaller than the group order and
+    /// [`ZkError::Encoding`] if a field is missing or has an unexpected shape.
+    pub fn from_dict(data: &serde_json::Value) -> Result<Self, ZkError> {
+        let t = match field(data, "t")? {
+            Value::String(hex) => hex_to_bytes(hex)
+                .ok_or_else(|| ZkError::Encoding("`t` is not a hex string".to_owned()))?,
+            value => byte_array(value, "t")?,
+        };
+        let t = P::decode(&t)?;
+        ensure_non_identity(&[t])?;
+
+        let s = match field(data, "s")? {
+            Value::String(text) => decode_scalar_str::<P::Scalar>(text)?,
+            Value::Number(n) => n
+                .as_u64()
+                .map(P::Scalar::from)
+                .ok_or_else(|| ZkError::Encoding("`s` is not an unsigned integer".to_owned()))?,
+            value => decode_scalar(&byte_array(value, "s")?)?,
+        };
+        Ok(Self::new(t, s))
+    }
+}
+
+/// Derives a nonce from the secret `x` and the statement with the RFC 6979 HMAC-DRBG.
+pub(crate) fn deterministic_nonce<P: ZkGroup>(
+    sid: &str,
+    pid: u32,
+    x: &SecretScalar<P::Scalar>,
+    points: &[P],
+    extra: &[u8],
+) -> SecretScalar<P::Scalar> {
+    let mut hasher = Sha256::new();
+    hasher.update(b"zk_proof/nonce");
+    hasher.update((sid.len() as u64).to_be_bytes());
+    hasher.update(sid.as_bytes());
+    hasher.update(pid.to_be_bytes());
+    for point in points {
+        hasher.update(point.to_bytes());
+    }
+    let h = reduce_be_bytes::<P::Scalar>(&hasher.finalize()).to_repr();
+    let mut key = x.expose_secret().to_repr();
+    let mut drbg = rfc6979::HmacDrbg::<Sha256>::new(key.as_ref(), h.as_ref(), extra);
+    key.as_mut().zeroize();
+    // Draw candidates until one is a canonical, non-zero scalar, as in RFC 6979 section 3.2.
+    loop {
+        let mut k = <P::Scalar as PrimeField>::Repr::default();
+        drbg.fill_bytes(k.as_mut());
+        let candidate = Option::<P::Scalar>::from(P::Scalar::from_repr(k));
+        k.as_mut().zeroize();
+        if let Some(k) = candidate.map(SecretScalar::new) {
+            if !bool::from(k.expose_secret().is_zero()) {
+                return k;
+            }
+        }
+    }
+}
+
+const FIELDS: &[&str; 2] = &["t", "s"];
+
+impl<P: ZkGroup> DLogProof<P> {
+    /// Serializes the proof as a struct with fields `t` and `s`, encoding the compressed `t`
+    /// with `T` and the big-endian `s` with `S`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::DLogProof;
+    /// # use zk_proof::SecretScalar;
+    /// # use zk_proof::encoding::{Decimal, Hex};
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+    /// let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+    ///
+    /// let mut json = vec![];
+    /// dlog_proof
+    ///     .serialize_with::<Hex, Decimal, _>(&mut serde_json::Serializer::new(&mut json))
+    ///     .unwrap();
+    /// let mut deserializer = serde_json::Deserializer::from_slice(&json);
+    /// let decoded = DLogProof::deserialize_with::<Hex, Decimal, _>(&mut deserializer).unwrap();
+    /// assert_eq!(decoded, dlog_proof);
+    /// ```
+    pub fn serialize_with<T: Encoding, S: Encoding, Ser: Serializer>(
+        &self,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        let mut state = serializer.serialize_struct("DLogProof", 2)?;
+        state.serialize_field("t", &Encoded::<T>::new(self.t.to_bytes().as_ref()))?;
+        state.serialize_field("s", &Encoded::<S>::new(self.s.to_repr().as_ref()))?;
+        state.end()
+    }
+
+    /// Deserializes a proof written by [`DLogProof::serialize_with`] with the same encodings.
+    ///
+    /// The point and scalar are validated like in [`DLogProof::from_dict`].
+    pub fn deserialize_with<'de, T: Encoding, S: Encoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let lens = [point_len::<P>(), scalar_len::<P::Scalar>()];
+        let visitor = PairVisitor::<T, S>::new("DLogProof", FIELDS, lens);
+        let (t, s) = deserializer.deserialize_struct("DLogProof", FIELDS, visitor)?;
+        let t = P::decode(&t)
+            .and_then(|t| ensure_non_identity(&[t]).map(|_| t))
+            .map_err(serde::de::Error::custom)?;
+        let s = decode_scalar(&s).map_err(serde::de::Error::custom)?;
+        Ok(Self::new(t, s))
+    }
+}
+
+impl<P: ZkGroup> EncodedProof for DLogProof<P> {
+    fn serialize_with<A: Encoding, B: Encoding, S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        DLogProof::serialize_with::<A, B, S>(self, serializer)
+    }
+
+    fn deserialize_with<'de, A: Encoding, B: Encoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        DLogProof::deserialize_with::<A, B, D>(deserializer)
+    }
+}
+
+/// Serializes `t` and `s` as [`Bytes`], matching [`DLogProof::to_dict`].
+impl<P: ZkGroup> Serialize for DLogProof<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_with::<Bytes, Bytes, S>(serializer)
+    }
+}
+
+impl<'de, P: ZkGroup> Deserialize<'de> for DLogProof<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize_with::<Bytes, Bytes, D>(deserializer)
+    }
+}
+
+impl<P: ZkGroup> FromStr for DLogProof<P> {
+    type Err = ZkError;
+
+    /// Parses the JSON text produced by [`DLogProof::to_str`] or the Python `DLogProof.to_str`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data: Value =
+            serde_json::from_str(s).map_err(|err| ZkError::Encoding(err.to_string()))?;
+        Self::from_dict(&data)
+    }
+}
+
+fn field<'a>(data: &'a Value, name: &str) -> Result<&'a Value, ZkError> {
+    data.get(name)
+        .ok_or_else(|| ZkError::Encoding(format!("missing field `{}`", name)))
+}
+
+fn byte_array(value: &Value, name: &str) -> Result<Vec<u8>, ZkError> {
+    let invalid = || ZkError::Encoding(format!("`{}` is not a byte array", name));
+    value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|b| {
+            b.as_u64()
+                .and_then(|b| u8::try_from(b).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Decodes a scalar from a decimal string or a `0x`-prefixed hex string.
+fn decode_scalar_str<F: PrimeField>(text: &str) -> Result<F, ZkError> {
+    let len = scalar_len::<F>();
+    let bytes = if text.starts_with("0x") {
+        hex_to_bytes(text).ok_or_else(|| ZkError::Encoding("`s` is not a hex string".to_owned()))?
+    } else {
+        if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ZkError::Encoding("`s` is not a decimal string".to_owned()));
+        }
+        decimal_to_bytes(text, len).ok_or(ZkError::NonCanonicalScalar)?
+    };
+    if bytes.len() > len {
+        return Err(ZkError::NonCanonicalScalar);
+    }
+    let mut padded = vec![0u8; len];
+    padded[len - bytes.len()..].copy_from_slice(&bytes);
+    decode_scalar(&padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    #[test]
+    fn test_hash_points() {
+        let sid = "sid";
+        let pid = 1;
+        let points = vec![ProjectivePoint::GENERATOR; 3];
+        let hash = DLogProof::hash_points(sid, pid, &points);
+        println!("{:?}", hash);
+    }
+
+    #[test]
+    fn test_hash_points_domain_separated() {
+        let points = vec![ProjectivePoint::GENERATOR; 3];
+        let hash = DLogProof::hash_points("sid", 1, &points);
+        let mut other = transcript::session::<Sha256>("sid", 1);
+        assert_ne!(
+            hash,
+            transcript::challenge(&mut other, b"DLEqProof", &points)
+        );
+        // Moving bytes between `sid` and `pid` changes the challenge.
+        assert_ne!(
+            hash,
+            DLogProof::hash_points("sid\0\0\0", 0x0100_0000, &points)
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let sid = "sid";
+        let pid = 1;
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+        assert!(dlog_proof.verify(sid, pid, y).is_ok());
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_pid() {
+        let sid = "sid";
+        let pid = 1;
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+        assert_eq!(
+            dlog_proof.verify(sid, pid, ProjectivePoint::GENERATOR),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_sid() {
+        let sid = "sid";
+        let pid = 1;
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+        assert_eq!(
+            dlog_proof.verify("abc", pid, ProjectivePoint::GENERATOR),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_with_base() {
+        let sid = "sid";
+        let pid = 1;
+        let h = ProjectivePoint::GENERATOR * generate_random_number();
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = h * x.expose_secret();
+        let dlog_proof = DLogProof::prove_with_base(sid, pid, &x, y, h);
+        assert!(dlog_proof.verify_with_base(sid, pid, y, h).is_ok());
+        assert_eq!(
+            dlog_proof.verify_with_base(sid, pid + 1, y, h),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            dlog_proof.verify(sid, pid, y),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_with_base_is_bound_to_base() {
+        // With y = x*G and h = k*G, y = (x/k)*h; a proof for G must not transfer to h.
+        let sid = "sid";
+        let pid = 1;
+        let k = generate_random_number();
+        let h = ProjectivePoint::GENERATOR * k;
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove(sid, pid, &x, y);
+        assert_eq!(
+            dlog_proof.verify_with_base(sid, pid, y, h),
+            Err(ZkError::ChallengeMismatch)
+        );
+
+        let x_over_k = SecretScalar::new(*x.expose_secret() * k.invert().unwrap());
+        let over_h = DLogProof::prove_with_base(sid, pid, &x_over_k, y, h);
+        assert!(over_h.verify_with_base(sid, pid, y, h).is_ok());
+        assert_eq!(over_h.verify(sid, pid, y), Err(ZkError::ChallengeMismatch));
+    }
+
+    #[test]
+    fn test_prove_deterministic() {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove_deterministic("sid", 1, &x, y, &[]);
+        assert!(dlog_proof.verify("sid", 1, y).is_ok());
+        assert_eq!(
+            dlog_proof,
+            DLogProof::prove_deterministic("sid", 1, &x, y, &[])
+        );
+
+        // Any change of statement or extra randomness yields an unrelated nonce.
+        let others = [
+            DLogProof::prove_deterministic("abc", 1, &x, y, &[]),
+            DLogProof::prove_deterministic("sid", 2, &x, y, &[]),
+            DLogProof::prove_deterministic("sid", 1, &x, y, b"extra"),
+        ];
+        for other in others {
+            assert_ne!(other.t, dlog_proof.t);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_nonce_known_answer() {
+        let x = SecretScalar::new(Scalar::from(42u64));
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let r = deterministic_nonce("sid", 1, &x, &[ProjectivePoint::GENERATOR, y], &[]);
+        assert_eq!(
+            hex::encode(r.expose_secret().to_bytes()),
+            "152d29ca654b855fbb70b4639c22eacbe8044ac66deac84acc42306727ff5542"
+        );
+    }
+
+    #[test]
+    fn test_prove_with_rng_is_reproducible() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let x = SecretScalar::random(&mut StdRng::seed_from_u64(1));
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let prove =
+            |seed| DLogProof::prove_with_rng(&mut StdRng::seed_from_u64(seed), "sid", 1, &x, y);
+        assert_eq!(prove(2), prove(2));
+        assert_ne!(prove(2), prove(3));
+        assert!(prove(2).verify("sid", 1, y).is_ok());
+
+        let mut rng: Box<dyn CryptoRngCore> = Box::new(StdRng::seed_from_u64(4));
+        let h = ProjectivePoint::GENERATOR * generate_random_number_with_rng(&mut *rng);
+        let dlog_proof =
+            DLogProof::prove_with_base_and_rng(&mut *rng, "sid", 1, &x, h * x.expose_secret(), h);
+        assert!(dlog_proof
+            .verify_with_base("sid", 1, h * x.expose_secret(), h)
+            .is_ok());
+        let items = [("sid", 1, y, prove(5))];
+        assert_eq!(DLogProof::batch_verify_with_rng(&mut *rng, &items), Ok(()));
+    }
+
+    fn batch(n: u32) -> Vec<(&'static str, u32, ProjectivePoint, DLogProof)> {
+        (0..n)
+            .map(|pid| {
+                let x = SecretScalar::random(&mut rand::thread_rng());
+                let y = ProjectivePoint::GENERATOR * x.expose_secret();
+                ("sid", pid, y, DLogProof::prove("sid", pid, &x, y))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        assert_eq!(<DLogProof>::batch_verify(&[]), Ok(()));
+        assert_eq!(DLogProof::batch_verify(&batch(1)), Ok(()));
+        assert_eq!(DLogProof::batch_verify(&batch(10)), Ok(()));
+    }
+
+    #[test]
+    fn test_batch_verify_reports_failed_index() {
+        let mut items = batch(10);
+        items[7].1 = 100;
+        assert_eq!(DLogProof::batch_verify(&items), Err(7));
+
+        let mut items = batch(10);
+        items[3].2 = ProjectivePoint::GENERATOR;
+        items[5].0 = "abc";
+        assert_eq!(DLogProof::batch_verify(&items), Err(3));
+    }
+
+    #[test]
+    fn test_batch_verify_rejects_cancelling_proofs() {
+        // Shifting s by +1 in one proof and -1 in another cancels out without random weights.
+        let mut items = batch(2);
+        items[0].3.s += Scalar::ONE;
+        items[1].3.s -= Scalar::ONE;
+        assert_eq!(DLogProof::batch_verify(&items), Err(0));
+    }
+
+    fn proof() -> DLogProof {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        DLogProof::prove("sid", 1, &x, y)
+    }
+
+    #[test]
+    fn test_dict_round_trip() {
+        let dlog_proof = proof();
+        let data = dlog_proof.to_dict();
+        assert_eq!(DLogProof::from_dict(&data), Ok(dlog_proof));
+        assert_eq!(dlog_proof.to_str().parse(), Ok(dlog_proof));
+    }
+
+    #[test]
+    fn test_from_dict_python_encoding() {
+        let dlog_proof = DLogProof::new(proof().t, Scalar::from(1234567890123u64));
+        let t = dlog_proof.t.to_affine().to_encoded_point(false);
+        let text = format!(
+            r#"{{"t": "{}", "s": "1234567890123"}}"#,
+            hex::encode(&t.as_bytes()[1..]),
+        );
+        assert_eq!(text.parse(), Ok(dlog_proof));
+
+        let data = serde_json::json!({
+            "t": hex::encode(t.as_bytes()),
+            "s": format!("0x{}", hex::encode(dlog_proof.s.to_bytes())),
+        });
+        assert_eq!(DLogProof::from_dict(&data), Ok(dlog_proof));
+    }
+
+    #[test]
+    fn test_from_dict_rejects_non_canonical_scalar() {
+        let mut data = proof().to_dict();
+        data["s"] = serde_json::json!(vec![0xffu8; 32]);
+        assert_eq!(
+            <DLogProof>::from_dict(&data),
+            Err(ZkError::NonCanonicalScalar)
+        );
+
+        // The group order itself must not be reduced to zero.
+        data["s"] = serde_json::json!(
+            "115792089237316195423570985008687907852837564279074904382605163141518161494337"
+        );
+        assert_eq!(
+            <DLogProof>::from_dict(&data),
+            Err(ZkError::NonCanonicalScalar)
+        );
+    }
+
+    #[test]
+    fn test_from_dict_rejects_malformed_input() {
+        let mut data = proof().to_dict();
+        let mut x_too_large = vec![0xffu8; 33];
+        x_too_large[0] = 2;
+        data["t"] = serde_json::json!(x_too_large);
+        assert_eq!(<DLogProof>::from_dict(&data), Err(ZkError::MalformedPoint));
+
+        let data = serde_json::json!({ "t": [1, 2, 300], "s": "1" });
+        assert!(matches!(
+            <DLogProof>::from_dict(&data),
+            Err(ZkError::Encoding(_))
+        ));
+        let data = serde_json::json!({ "s": "1" });
+        assert!(matches!(
+            <DLogProof>::from_dict(&data),
+            Err(ZkError::Encoding(_))
+        ));
+        assert!(matches!(
+            "not json".parse::<DLogProof>(),
+            Err(ZkError::Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_serde_json_matches_to_dict() {
+        let dlog_proof = proof();
+        assert_eq!(
+            serde_json::to_value(dlog_proof).unwrap(),
+            dlog_proof.to_dict()
+        );
+        let decoded: DLogProof = serde_json::from_value(dlog_proof.to_dict()).unwrap();
+        assert_eq!(decoded, dlog_proof);
+    }
+
+    #[test]
+    fn test_serde_encodings() {
+        use crate::encoding::{as_base64, as_bytes, as_decimal, as_hex};
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Message {
+            #[serde(with = "as_bytes")]
+            bytes: DLogProof,
+            #[serde(with = "as_hex")]
+            hex: DLogProof,
+            #[serde(with = "as_base64")]
+            base64: DLogProof,
+            #[serde(with = "as_decimal")]
+            decimal: DLogProof,
+        }
+
+        let dlog_proof = proof();
+        let message = Message {
+            bytes: dlog_proof,
+            hex: dlog_proof,
+            base64: dlog_proof,
+            decimal: dlog_proof,
+        };
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["hex"]["s"], hex::encode(dlog_proof.s.to_bytes()));
+        assert_eq!(
+            json["decimal"]["s"],
+            crate::encoding::bytes_to_decimal(&dlog_proof.s.to_bytes())
+        );
+        assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+
+        let mut cbor = vec![];
+        ciborium::into_writer(&message, &mut cbor).unwrap();
+        let decoded: Message = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(decoded, message);
+
+        let encoded = bincode::serialize(&message).unwrap();
+        let decoded: Message = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_serde_rejects_invalid_proofs() {
+        let mut data = proof().to_dict();
+        data["s"] = serde_json::json!(vec![0xffu8; 32]);
+        let err = serde_json::from_value::<DLogProof>(data).unwrap_err();
+        assert!(err.to_string().contains("non-canonical scalar"));
+
+        use crate::encoding::Hex;
+        let json = serde_json::json!({ "t": "zz", "s": "00" }).to_string();
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert!(<DLogProof>::deserialize_with::<Hex, Hex, _>(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_identity() {
+        let identity = ProjectivePoint::IDENTITY;
+        let trivial = DLogProof::new(identity, Scalar::ZERO);
+        assert_eq!(
+            trivial.verify("sid", 1, identity),
+            Err(ZkError::IdentityPoint)
+        );
+
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let zero_t = DLogProof::new(identity, Scalar::ZERO);
+        assert_eq!(zero_t.verify("sid", 1, y), Err(ZkError::IdentityPoint));
+
+        let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+        assert_eq!(
+            dlog_proof.verify_with_base("sid", 1, y, identity),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_rejects_identity() {
+        let mut items = batch(4);
+        items[2].2 = ProjectivePoint::IDENTITY;
+        items[2].3 = DLogProof::new(ProjectivePoint::IDENTITY, Scalar::ZERO);
+        assert_eq!(DLogProof::batch_verify(&items), Err(2));
+
+        // An earlier proof that fails only the equation is still the one reported.
+        items[1].1 += 1;
+        assert_eq!(DLogProof::batch_verify(&items), Err(1));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_identity() {
+        let mut data = proof().to_dict();
+        data["t"] = serde_json::json!([0]);
+        assert_eq!(<DLogProof>::from_dict(&data), Err(ZkError::IdentityPoint));
+        assert!(serde_json::from_value::<DLogProof>(data).is_err());
+
+        let mut data = proof().to_dict();
+        data["t"] = serde_json::json!(vec![0u8; 33]);
+        assert_eq!(<DLogProof>::from_dict(&data), Err(ZkError::MalformedPoint));
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn test_p256() {
+        use p256::{ProjectivePoint, Scalar};
+
+        let x = SecretScalar::<Scalar>::random(&mut rand::thread_rng());
+        let y = ProjectivePoint::GENERATOR * x.expose_secret();
+        let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+        assert!(dlog_proof.verify("sid", 1, y).is_ok());
+        assert_eq!(
+            dlog_proof.verify("sid", 2, y),
+            Err(ZkError::ChallengeMismatch)
+        );
+
+        let deterministic = DLogProof::prove_deterministic("sid", 1, &x, y, &[]);
+        assert!(deterministic.verify("sid", 1, y).is_ok());
+        assert_eq!(
+            deterministic,
+            DLogProof::prove_deterministic("sid", 1, &x, y, &[])
+        );
+
+        let data = dlog_proof.to_dict();
+        assert_eq!(DLogProof::from_dict(&data), Ok(dlog_proof));
+        let json = serde_json::to_string(&dlog_proof).unwrap();
+        assert_eq!(serde_json::from_str(&json).ok(), Some(dlog_proof));
+
+        let items = [("sid", 1, y, dlog_proof), ("sid", 1, y, deterministic)];
+        assert_eq!(DLogProof::batch_verify(&items), Ok(()));
+    }
+}
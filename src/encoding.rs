@@ -0,0 +1,469 @@
+//! Selectable serde representations for the byte strings inside proofs.
+//!
+//! [`DLogProof`](crate::DLogProof) serializes `t` and `s` and
+//! [`CompactDLogProof`](crate::CompactDLogProof) serializes `c` and `s` as byte arrays by
+//! default. The [`Encoding`] implementations here select another representation, either through
+//! [`DLogProof::serialize_with`](crate::DLogProof::serialize_with) or through the `as_*`
+//! modules for use with `#[serde(with = "...")]`:
+//!
+//! ```
+//! # use zk_proof::{DLogProof, SecretScalar};
+//! # use k256::ProjectivePoint;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Message {
+//!     #[serde(with = "zk_proof::encoding::as_hex")]
+//!     proof: DLogProof,
+//! }
+//!
+//! let x = SecretScalar::random(&mut rand::thread_rng());
+//! let y = ProjectivePoint::GENERATOR * x.expose_secret();
+//! let message = Message { proof: DLogProof::prove("sid", 1, &x, y) };
+//! let json = serde_json::to_string(&message).unwrap();
+//! let decoded: Message = serde_json::from_str(&json).unwrap();
+//! assert_eq!(decoded.proof, message.proof);
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use k256::elliptic_curve::{
+    generic_array::typenum::Unsigned,
+    sec1::{EncodedPoint, FromEncodedPoint, ModulusSize},
+    AffinePoint, CurveArithmetic, FieldBytesSize, PrimeField, ProjectivePoint,
+};
+use serde::{
+    de::{self, DeserializeSeed, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::ZkError;
+
+/// A serde representation of a fixed-length big-endian byte string.
+pub trait Encoding {
+    /// Serializes `bytes` in this representation.
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes a byte string of nominal length `len` from this representation.
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        len: usize,
+    ) -> Result<Vec<u8>, D::Error>;
+}
+
+/// A proof made of two byte strings whose encodings can be selected independently.
+///
+/// Implemented by [`DLogProof`](crate::DLogProof) and
+/// [`CompactDLogProof`](crate::CompactDLogProof) for the `as_*` adapters.
+pub trait EncodedProof: Sized {
+    /// Serializes the proof with its first field in `A` and its second field in `B`.
+    fn serialize_with<A: Encoding, B: Encoding, S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes a proof written by [`EncodedProof::serialize_with`] with the same
+    /// encodings.
+    fn deserialize_with<'de, A: Encoding, B: Encoding, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+/// Raw bytes: an array of integers in JSON and a byte string in CBOR or bincode.
+pub struct Bytes;
+
+/// A lowercase hex string; decoding also accepts uppercase digits and a `0x` prefix.
+pub struct Hex;
+
+/// A standard, padded base64 string.
+pub struct Base64;
+
+/// A decimal big-integer string, like the Python `BigIntegerField`.
+pub struct Decimal;
+
+impl Encoding for Bytes {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        _len: usize,
+    ) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a byte array")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+impl Encoding for Hex {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        _len: usize,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        hex_to_bytes(&text).ok_or_else(|| de::Error::custom("invalid hex string"))
+    }
+}
+
+impl Encoding for Base64 {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        _len: usize,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        BASE64.decode(text).map_err(de::Error::custom)
+    }
+}
+
+impl Encoding for Decimal {
+    fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bytes_to_decimal(bytes))
+    }
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        len: usize,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(de::Error::custom("invalid decimal string"));
+        }
+        decimal_to_bytes(&text, len)
+            .ok_or_else(|| de::Error::custom(format!("integer does not fit in {} bytes", len)))
+    }
+}
+
+/// Serializes a byte string with the encoding `E`.
+pub(crate) struct Encoded<'a, E> {
+    bytes: &'a [u8],
+    encoding: PhantomData<E>,
+}
+
+impl<'a, E> Encoded<'a, E> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            encoding: PhantomData,
+        }
+    }
+}
+
+impl<E: Encoding> Serialize for Encoded<'_, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        E::serialize_bytes(self.bytes, serializer)
+    }
+}
+
+/// Deserializes a byte string of nominal length `len` with the encoding `E`.
+pub(crate) struct EncodedSeed<E> {
+    len: usize,
+    encoding: PhantomData<E>,
+}
+
+impl<E> EncodedSeed<E> {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            len,
+            encoding: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: Encoding> DeserializeSeed<'de> for EncodedSeed<E> {
+    type Value = Vec<u8>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        E::deserialize_bytes(deserializer, self.len)
+    }
+}
+
+/// Visits a struct made of two encoded byte strings, as a map or as a sequence.
+pub(crate) struct PairVisitor<A, B> {
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+    lens: [usize; 2],
+    encodings: PhantomData<(A, B)>,
+}
+
+impl<A, B> PairVisitor<A, B> {
+    pub(crate) fn new(
+        name: &'static str,
+        fields: &'static [&'static str; 2],
+        lens: [usize; 2],
+    ) -> Self {
+        Self {
+            name,
+            fields,
+            lens,
+            encodings: PhantomData,
+        }
+    }
+}
+
+impl<'de, A: Encoding, B: Encoding> Visitor<'de> for PairVisitor<A, B> {
+    type Value = (Vec<u8>, Vec<u8>);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "struct {}", self.name)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let first = seq
+            .next_element_seed(EncodedSeed::<A>::new(self.lens[0]))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let second = seq
+            .next_element_seed(EncodedSeed::<B>::new(self.lens[1]))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((first, second))
+    }
+
+    fn visit_map<M: de::MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let (mut first, mut second) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.fields[0] && first.is_none() {
+                first = Some(map.next_value_seed(EncodedSeed::<A>::new(self.lens[0]))?);
+            } else if key == self.fields[1] && second.is_none() {
+                second = Some(map.next_value_seed(EncodedSeed::<B>::new(self.lens[1]))?);
+            } else if key == self.fields[0] || key == self.fields[1] {
+                return Err(de::Error::custom(format!("duplicate field `{}`", key)));
+            } else {
+                return Err(de::Error::unknown_field(&key, self.fields));
+            }
+        }
+        Ok((
+            first.ok_or_else(|| de::Error::missing_field(self.fields[0]))?,
+            second.ok_or_else(|| de::Error::missing_field(self.fields[1]))?,
+        ))
+    }
+}
+
+macro_rules! with_encoding {
+    ($module:ident, $encoding:ty, $doc:literal) => {
+        #[doc = $doc]
+        pub mod $module {
+            use serde::{Deserializer, Serializer};
+
+            use super::EncodedProof;
+
+            /// Serializes `proof` with both fields in this module's encoding.
+            pub fn serialize<T: EncodedProof, S: Serializer>(
+                proof: &T,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                proof.serialize_with::<$encoding, $encoding, S>(serializer)
+            }
+
+            /// Deserializes a proof with both fields in this module's encoding.
+            pub fn deserialize<'de, T: EncodedProof, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<T, D::Error> {
+                T::deserialize_with::<$encoding, $encoding, D>(deserializer)
+            }
+        }
+    };
+}
+
+with_encoding!(
+    as_bytes,
+    super::Bytes,
+    "`#[serde(with)]` adapter encoding both fields of a proof as [`Bytes`]."
+);
+with_encoding!(
+    as_hex,
+    super::Hex,
+    "`#[serde(with)]` adapter encoding both fields of a proof as [`Hex`]."
+);
+with_encoding!(
+    as_base64,
+    super::Base64,
+    "`#[serde(with)]` adapter encoding both fields of a proof as [`Base64`]."
+);
+with_encoding!(
+    as_decimal,
+    super::Decimal,
+    "`#[serde(with)]` adapter encoding both fields of a proof as [`Decimal`]."
+);
+
+/// Decodes a point from its SEC1 compressed or uncompressed encoding, or from the raw
+/// `x || y` encoding used by python-ecdsa.
+///
+/// Decoding checks that the point is on the curve and rejects the point at infinity.
+pub(crate) fn decode_sec1<C>(bytes: &[u8]) -> Result<ProjectivePoint<C>, ZkError>
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+    AffinePoint<C>: FromEncodedPoint<C>,
+{
+    let encoded = if bytes.len() == <FieldBytesSize<C> as ModulusSize>::UntaggedPointSize::USIZE {
+        EncodedPoint::<C>::from_untagged_bytes(bytes.into())
+    } else {
+        EncodedPoint::<C>::from_bytes(bytes).map_err(|_| ZkError::MalformedPoint)?
+    };
+    if encoded.is_identity() {
+        return Err(ZkError::IdentityPoint);
+    }
+    Option::<AffinePoint<C>>::from(AffinePoint::<C>::from_encoded_point(&encoded))
+        .map(ProjectivePoint::<C>::from)
+        .ok_or(ZkError::MalformedPoint)
+}
+
+/// Decodes a scalar from its canonical encoding, rejecting values that are not smaller than the
+/// group order.
+pub(crate) fn decode_scalar<F: PrimeField>(bytes: &[u8]) -> Result<F, ZkError> {
+    let mut repr = F::Repr::default();
+    if bytes.len() != repr.as_ref().len() {
+        return Err(ZkError::Encoding(format!(
+            "expected {} scalar bytes, got {}",
+            repr.as_ref().len(),
+            bytes.len()
+        )));
+    }
+    repr.as_mut().copy_from_slice(bytes);
+    Option::from(F::from_repr(repr)).ok_or(ZkError::NonCanonicalScalar)
+}
+
+/// Parses a decimal string into a big-endian byte array of `len` bytes.
+///
+/// Returns `None` if the string is empty, contains anything but ASCII digits, or the value does
+/// not fit in `len` bytes.
+pub(crate) fn decimal_to_bytes(decimal: &str, len: usize) -> Option<Vec<u8>> {
+    if decimal.is_empty() {
+        return None;
+    }
+    let mut bytes = vec![0u8; len];
+    for ch in decimal.chars() {
+        let mut carry = ch.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
+/// Formats a big-endian byte string as a decimal integer.
+pub(crate) fn bytes_to_decimal(bytes: &[u8]) -> String {
+    let mut digits = vec![];
+    let mut value = bytes.to_vec();
+    while value.iter().any(|b| *b != 0) {
+        let mut remainder = 0u32;
+        for byte in value.iter_mut() {
+            let acc = (remainder << 8) | u32::from(*byte);
+            *byte = (acc / 10) as u8;
+            remainder = acc % 10;
+        }
+        digits.push(char::from(b'0' + remainder as u8));
+    }
+    if digits.is_empty() {
+        return "0".to_owned();
+    }
+    digits.iter().rev().collect()
+}
+
+/// Decodes a hex string, with or without a `0x` prefix.
+pub(crate) fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::{
+        elliptic_curve::{group::GroupEncoding, sec1::ToEncodedPoint},
+        ProjectivePoint, Scalar, Secp256k1,
+    };
+
+    fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, ZkError> {
+        decode_sec1::<Secp256k1>(bytes)
+    }
+
+    #[test]
+    fn test_decimal_to_bytes() {
+        let mut two_pow_64 = vec![0u8; 32];
+        two_pow_64[23] = 1;
+        assert_eq!(
+            decimal_to_bytes("18446744073709551616", 32),
+            Some(two_pow_64.clone())
+        );
+        assert_eq!(decimal_to_bytes("0", 1), Some(vec![0]));
+        assert_eq!(bytes_to_decimal(&[0, 0]), "0");
+        assert_eq!(bytes_to_decimal(&[1, 0]), "256");
+        assert_eq!(bytes_to_decimal(&two_pow_64), "18446744073709551616");
+        assert_eq!(decimal_to_bytes("256", 2), Some(vec![1, 0]));
+        assert_eq!(decimal_to_bytes("65536", 2), None);
+        assert_eq!(decimal_to_bytes("12a", 32), None);
+        assert_eq!(decimal_to_bytes("", 32), None);
+    }
+
+    #[test]
+    fn test_hex_to_bytes() {
+        assert_eq!(hex_to_bytes("0x01ff"), Some(vec![1, 255]));
+        assert_eq!(hex_to_bytes("01FF"), Some(vec![1, 255]));
+        assert_eq!(hex_to_bytes("1ff"), None);
+        assert_eq!(hex_to_bytes("zz"), None);
+    }
+
+    #[test]
+    fn test_decode_point_encodings() {
+        let point = ProjectivePoint::GENERATOR * Scalar::from(7u64);
+        let uncompressed = point.to_affine().to_encoded_point(false);
+        assert_eq!(decode_point(&point.to_bytes()), Ok(point));
+        assert_eq!(decode_point(uncompressed.as_bytes()), Ok(point));
+        assert_eq!(decode_point(&uncompressed.as_bytes()[1..]), Ok(point));
+        let mut x_too_large = [0xffu8; 33];
+        x_too_large[0] = 2;
+        assert_eq!(decode_point(&x_too_large), Err(ZkError::MalformedPoint));
+        assert_eq!(decode_point(&[4u8; 10]), Err(ZkError::MalformedPoint));
+        assert_eq!(decode_point(&[0]), Err(ZkError::IdentityPoint));
+    }
+
+    #[test]
+    fn test_decode_scalar_rejects_non_canonical() {
+        assert_eq!(
+            decode_scalar::<Scalar>(&[0xff; 32]),
+            Err(ZkError::NonCanonicalScalar)
+        );
+        assert!(matches!(
+            decode_scalar::<Scalar>(&[1; 31]),
+            Err(ZkError::Encoding(_))
+        ));
+        let one = Scalar::ONE.to_bytes();
+        assert_eq!(decode_scalar(&one), Ok(Scalar::ONE));
+    }
+}
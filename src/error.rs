@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Errors returned when decoding or checking proofs.
+///
+/// The variants separate malformed inputs ([`MalformedPoint`](ZkError::MalformedPoint),
+/// [`NonCanonicalScalar`](ZkError::NonCanonicalScalar),
+/// [`IdentityPoint`](ZkError::IdentityPoint), [`Encoding`](ZkError::Encoding)) from well-formed
+/// proofs that do not hold for the given statement
+/// ([`ChallengeMismatch`](ZkError::ChallengeMismatch)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZkError {
+    /// The bytes do not encode a point on the curve.
+    MalformedPoint,
+    /// The value is not a canonical scalar, i.e. it is not smaller than the group order.
+    NonCanonicalScalar,
+    /// A point that must not be the point at infinity is the identity.
+    IdentityPoint,
+    /// The verification equation does not hold for the challenge derived from the transcript,
+    /// e.g. because the proof was made for another statement, public key, sid or pid.
+    ChallengeMismatch,
+    /// The Fiat-Shamir transcript could not be built from the given inputs.
+    Transcript(String),
+    /// The input does not have the expected shape or textual encoding.
+    Encoding(String),
+    /// A secret share does not match the public commitments of its dealer.
+    InvalidShare,
+    /// The prover's or a protocol participant's inputs do not describe a valid statement, e.g. a
+    /// value outside the range being proven.
+    InvalidInput(String),
+}
+
+impl fmt::Display for ZkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkError::MalformedPoint => write!(f, "malformed curve point"),
+            ZkError::NonCanonicalScalar => write!(f, "non-canonical scalar"),
+            ZkError::IdentityPoint => write!(f, "unexpected identity point"),
+            ZkError::ChallengeMismatch => write!(f, "proof does not match the challenge"),
+            ZkError::Transcript(msg) => write!(f, "invalid transcript: {}", msg),
+            ZkError::Encoding(msg) => write!(f, "invalid encoding: {}", msg),
+            ZkError::InvalidShare => write!(f, "share does not match the commitments"),
+            ZkError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ZkError {}
@@ -0,0 +1,855 @@
+//! FROST threshold Schnorr signatures, following RFC 9591 with the FROST(secp256k1, SHA-256)
+//! ciphersuite.
+//!
+//! Any `t` holders of shares of a key, e.g. from [`dkg`](crate::dkg) or [`vss`],
+//! sign a message in two rounds with the help of a coordinator:
+//!
+//! 1. **Commit** ([`commit`]): every signer draws a hiding and a binding nonce, keeps the
+//!    [`SigningNonces`] and sends the [`SigningCommitment`] to the coordinator.
+//! 2. **Sign** ([`sign`]): the coordinator sends the message and the commitments of the chosen
+//!    signers to each of them, and every signer answers with its [`PartialSignature`].
+//!
+//! The coordinator checks partial signatures with [`verify_partial`] and sums them with
+//! [`aggregate`] into a [`Signature`], which verifies against the group public key like a
+//! single-party Schnorr signature. Every signer's nonce is bound to the message and the whole
+//! commitment list through its binding factor, so the signers need not trust the coordinator.
+//! Nonces must sign only once, which is why [`sign`] consumes them.
+//!
+//! ```
+//! use zk_proof::{frost, vss, SecretScalar};
+//!
+//! let secret = SecretScalar::random(&mut rand::thread_rng());
+//! let (commitment, _, shares) = vss::share("sid", 0, &secret, 2, 3).unwrap();
+//! let public_key = commitment.public_key();
+//!
+//! let signers = [&shares[0], &shares[2]];
+//! let (nonces, commitments): (Vec<_>, Vec<_>) =
+//!     signers.iter().map(|share| frost::commit(share)).unzip();
+//!
+//! let msg = b"message";
+//! let partials: Vec<_> = signers
+//!     .iter()
+//!     .zip(nonces)
+//!     .map(|(share, nonces)| frost::sign(share, public_key, nonces, msg, &commitments).unwrap())
+//!     .collect();
+//! for partial in &partials {
+//!     let verification_share = commitment.share_point(partial.pid());
+//!     assert!(frost::verify_partial(public_key, verification_share, msg, &commitments, partial)
+//!         .is_ok());
+//! }
+//!
+//! let signature = frost::aggregate(public_key, msg, &commitments, &partials).unwrap();
+//! assert!(signature.verify(public_key, msg).is_ok());
+//! ```
+
+use k256::{
+    elliptic_curve::{group::GroupEncoding, rand_core::CryptoRngCore, zeroize::Zeroizing},
+    ProjectivePoint, Scalar,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    encoding::{decode_scalar, decode_sec1},
+    group::ensure_non_identity,
+    hash::hash_to_scalar,
+    vss::{self, SecretShare},
+    SecretScalar, ZkError,
+};
+
+/// The context string of the FROST(secp256k1, SHA-256) ciphersuite.
+const CONTEXT: &[u8] = b"FROST-secp256k1-SHA256-v1";
+
+const POINT_LEN: usize = 33;
+const SCALAR_LEN: usize = 32;
+
+/// The hiding and binding nonces of a signer for one signature.
+///
+/// The nonces are wiped when dropped and cannot be cloned, so that they sign only once.
+#[derive(Debug)]
+pub struct SigningNonces {
+    hiding: SecretScalar,
+    binding: SecretScalar,
+    commitment: SigningCommitment,
+}
+
+impl SigningNonces {
+    /// Returns the commitment to the nonces.
+    pub fn commitment(&self) -> &SigningCommitment {
+        &self.commitment
+    }
+}
+
+/// The commitments `D_i = d_i*G` and `E_i = e_i*G` to the hiding and binding nonces of the
+/// signer `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningCommitment {
+    pid: u32,
+    hiding: ProjectivePoint,
+    binding: ProjectivePoint,
+}
+
+impl SigningCommitment {
+    /// Creates the commitment of the signer `pid`.
+    pub fn new(pid: u32, hiding: ProjectivePoint, binding: ProjectivePoint) -> Self {
+        Self {
+            pid,
+            hiding,
+            binding,
+        }
+    }
+
+    /// Returns the id of the signer.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns the commitment `D_i` to the hiding nonce.
+    pub fn hiding(&self) -> ProjectivePoint {
+        self.hiding
+    }
+
+    /// Returns the commitment `E_i` to the binding nonce.
+    pub fn binding(&self) -> ProjectivePoint {
+        self.binding
+    }
+}
+
+/// The signature share `z_i` of the signer `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature {
+    pid: u32,
+    z: Scalar,
+}
+
+impl PartialSignature {
+    /// Creates the signature share of the signer `pid`.
+    pub fn new(pid: u32, z: Scalar) -> Self {
+        Self { pid, z }
+    }
+
+    /// Returns the id of the signer.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Returns the signature share `z_i`.
+    pub fn z(&self) -> &Scalar {
+        &self.z
+    }
+}
+
+/// A Schnorr signature `(R, z)` with `z*G == R + c*Y` for the challenge
+/// `c = H2(R || Y || msg)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    r: ProjectivePoint,
+    z: Scalar,
+}
+
+impl Signature {
+    /// Creates a signature from the commitment `R` and the response `z`.
+    pub fn new(r: ProjectivePoint, z: Scalar) -> Self {
+        Self { r, z }
+    }
+
+    /// Returns the group commitment `R`.
+    pub fn r(&self) -> &ProjectivePoint {
+        &self.r
+    }
+
+    /// Returns the response `z`.
+    pub fn z(&self) -> &Scalar {
+        &self.z
+    }
+
+    /// Verifies that `z*G == R + c*Y` for the public key `Y` and the message `msg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if `Y` or `R` is the point at infinity and
+    /// [`ZkError::ChallengeMismatch`] if the signature does not hold.
+    pub fn verify(&self, public_key: ProjectivePoint, msg: &[u8]) -> Result<(), ZkError> {
+        ensure_non_identity(&[public_key, self.r])?;
+        let c = challenge(&self.r, &public_key, msg);
+        if ProjectivePoint::GENERATOR * self.z != self.r + public_key * c {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Encodes the signature as the 33-byte compressed `R` followed by the 32-byte `z`.
+    pub fn to_bytes(&self) -> [u8; POINT_LEN + SCALAR_LEN] {
+        let mut bytes = [0u8; POINT_LEN + SCALAR_LEN];
+        bytes[..POINT_LEN].copy_from_slice(&self.r.to_bytes());
+        bytes[POINT_LEN..].copy_from_slice(&self.z.to_bytes());
+        bytes
+    }
+
+    /// Decodes a signature encoded with [`Signature::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if the length is not 65 bytes, [`ZkError::MalformedPoint`]
+    /// or [`ZkError::IdentityPoint`] for an invalid `R` and [`ZkError::NonCanonicalScalar`] if
+    /// `z` is not smaller than the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        if bytes.len() != POINT_LEN + SCALAR_LEN {
+            return Err(ZkError::Encoding(format!(
+                "expected {} signature bytes, got {}",
+                POINT_LEN + SCALAR_LEN,
+                bytes.len()
+            )));
+        }
+        let r = decode_sec1::<k256::Secp256k1>(&bytes[..POINT_LEN])?;
+        let z = decode_scalar(&bytes[POINT_LEN..])?;
+        Ok(Self::new(r, z))
+    }
+}
+
+/// Draws the nonces of the signer holding `share` for one signature.
+///
+/// # Returns
+///
+/// The nonces to keep until [`sign`] and the [`SigningCommitment`] to send to the coordinator.
+pub fn commit(share: &SecretShare) -> (SigningNonces, SigningCommitment) {
+    commit_with_rng(&mut rand::thread_rng(), share)
+}
+
+/// Draws the nonces like [`commit`] from `rng`.
+///
+/// As in RFC 9591, each nonce hashes 32 random bytes together with the share, so that a weak
+/// `rng` alone does not reveal it.
+pub fn commit_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    share: &SecretShare,
+) -> (SigningNonces, SigningCommitment) {
+    let mut hiding_random = Zeroizing::new([0u8; 32]);
+    let mut binding_random = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(hiding_random.as_mut());
+    rng.fill_bytes(binding_random.as_mut());
+    commit_with_randomness(&hiding_random, &binding_random, share)
+}
+
+/// Derives the nonces like [`commit`] from the given random bytes, e.g. those of test vectors.
+pub(crate) fn commit_with_randomness(
+    hiding_random: &[u8; 32],
+    binding_random: &[u8; 32],
+    share: &SecretShare,
+) -> (SigningNonces, SigningCommitment) {
+    let hiding = nonce(hiding_random, share.value());
+    let binding = nonce(binding_random, share.value());
+    let commitment = SigningCommitment::new(
+        share.index(),
+        ProjectivePoint::GENERATOR * hiding.expose_secret(),
+        ProjectivePoint::GENERATOR * binding.expose_secret(),
+    );
+    let nonces = SigningNonces {
+        hiding,
+        binding,
+        commitment,
+    };
+    (nonces, commitment)
+}
+
+/// Computes the signature share `z_i = d_i + e_i*rho_i + lambda_i*x_i*c` of the signer holding
+/// `share`.
+///
+/// # Arguments
+///
+/// * `share` - The signer's share `x_i` of the secret key.
+/// * `public_key` - The group public key `Y`.
+/// * `nonces` - The nonces drawn by [`commit`] for this signature.
+/// * `msg` - The message to sign.
+/// * `commitments` - The commitments of all signers, in any order.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `commitments` is empty, names a signer twice or an
+/// invalid signer, or does not hold the commitment of `nonces` for this signer, and
+/// [`ZkError::IdentityPoint`] if a commitment is the point at infinity.
+pub fn sign(
+    share: &SecretShare,
+    public_key: ProjectivePoint,
+    nonces: SigningNonces,
+    msg: &[u8],
+    commitments: &[SigningCommitment],
+) -> Result<PartialSignature, ZkError> {
+    let signing = Signing::new(public_key, msg, commitments)?;
+    let pid = share.index();
+    if nonces.commitment.pid != pid || signing.commitment(pid) != Some(&nonces.commitment) {
+        return Err(ZkError::InvalidInput(format!(
+            "the commitments do not hold the commitment of signer {}",
+            pid
+        )));
+    }
+    let lambda = signing.lagrange_coefficient(pid)?;
+    let z = nonces.hiding.expose_secret()
+        + *nonces.binding.expose_secret() * signing.binding_factor(pid)
+        + lambda * share.value().expose_secret() * signing.c;
+    Ok(PartialSignature::new(pid, z))
+}
+
+/// Verifies the signature share of one signer, so that the coordinator can tell which signers
+/// misbehaved.
+///
+/// # Arguments
+///
+/// * `public_key` - The group public key `Y`.
+/// * `verification_share` - The signer's public share `Y_i = x_i*G`.
+/// * `msg` - The signed message.
+/// * `commitments` - The commitments of all signers.
+/// * `partial` - The signer's signature share.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] for an invalid commitment list or one without the signer,
+/// [`ZkError::IdentityPoint`] if a commitment is the point at infinity and
+/// [`ZkError::ChallengeMismatch`] if the share does not hold.
+pub fn verify_partial(
+    public_key: ProjectivePoint,
+    verification_share: ProjectivePoint,
+    msg: &[u8],
+    commitments: &[SigningCommitment],
+    partial: &PartialSignature,
+) -> Result<(), ZkError> {
+    let signing = Signing::new(public_key, msg, commitments)?;
+    let commitment = signing.commitment(partial.pid).ok_or_else(|| {
+        ZkError::InvalidInput(format!("signer {} has no commitment", partial.pid))
+    })?;
+    let lambda = signing.lagrange_coefficient(partial.pid)?;
+    let r_i = commitment.hiding + commitment.binding * signing.binding_factor(partial.pid);
+    if ProjectivePoint::GENERATOR * partial.z != r_i + verification_share * (signing.c * lambda) {
+        return Err(ZkError::ChallengeMismatch);
+    }
+    Ok(())
+}
+
+/// Sums the signature shares of all signers into the signature of `msg` and verifies it.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] for an invalid commitment list or unless `partials` holds
+/// exactly one share per commitment, [`ZkError::IdentityPoint`] if a commitment is the point at
+/// infinity and [`ZkError::ChallengeMismatch`] if the signature does not hold; the faulty shares
+/// are then found with [`verify_partial`].
+pub fn aggregate(
+    public_key: ProjectivePoint,
+    msg: &[u8],
+    commitments: &[SigningCommitment],
+    partials: &[PartialSignature],
+) -> Result<Signature, ZkError> {
+    let signing = Signing::new(public_key, msg, commitments)?;
+    let mut pids: Vec<_> = partials.iter().map(PartialSignature::pid).collect();
+    pids.sort_unstable();
+    if pids != signing.pids() {
+        return Err(ZkError::InvalidInput(
+            "the signature shares do not match the commitments".to_string(),
+        ));
+    }
+    let z = partials.iter().map(|partial| partial.z).sum();
+    let signature = Signature::new(signing.r, z);
+    signature.verify(public_key, msg)?;
+    Ok(signature)
+}
+
+/// The values every signer derives from the message and the commitment list.
+struct Signing {
+    /// The commitments, sorted by signer.
+    commitments: Vec<SigningCommitment>,
+    /// The binding factor `rho_i` of every signer, in the order of `commitments`.
+    binding_factors: Vec<Scalar>,
+    /// The group commitment `R`.
+    r: ProjectivePoint,
+    /// The challenge `c`.
+    c: Scalar,
+}
+
+impl Signing {
+    fn new(
+        public_key: ProjectivePoint,
+        msg: &[u8],
+        commitments: &[SigningCommitment],
+    ) -> Result<Self, ZkError> {
+        if commitments.is_empty() {
+            return Err(ZkError::InvalidInput("no commitments".to_string()));
+        }
+        let mut commitments = commitments.to_vec();
+        commitments.sort_unstable_by_key(SigningCommitment::pid);
+        for (position, commitment) in commitments.iter().enumerate() {
+            if commitment.pid == 0 {
+                return Err(ZkError::InvalidInput("signer id 0".to_string()));
+            }
+            if position > 0 && commitments[position - 1].pid == commitment.pid {
+                return Err(ZkError::InvalidInput(format!(
+                    "more than one commitment from signer {}",
+                    commitment.pid
+                )));
+            }
+            ensure_non_identity(&[commitment.hiding, commitment.binding])?;
+        }
+        ensure_non_identity(&[public_key])?;
+
+        let mut encoded = Vec::with_capacity(commitments.len() * (SCALAR_LEN + 2 * POINT_LEN));
+        for commitment in &commitments {
+            encoded.extend_from_slice(&Scalar::from(commitment.pid).to_bytes());
+            encoded.extend_from_slice(&commitment.hiding.to_bytes());
+            encoded.extend_from_slice(&commitment.binding.to_bytes());
+        }
+        let mut prefix = public_key.to_bytes().to_vec();
+        prefix.extend_from_slice(&h(b"msg", msg));
+        prefix.extend_from_slice(&h(b"com", &encoded));
+        let binding_factors: Vec<_> = commitments
+            .iter()
+            .map(|commitment| {
+                let mut input = prefix.clone();
+                input.extend_from_slice(&Scalar::from(commitment.pid).to_bytes());
+                hash_to_scalar::<_, Sha256>(&input, &dst(b"rho"))
+            })
+            .collect();
+        let r = commitments
+            .iter()
+            .zip(&binding_factors)
+            .map(|(commitment, rho)| commitment.hiding + commitment.binding * rho)
+            .sum();
+        let c = challenge(&r, &public_key, msg);
+        Ok(Self {
+            commitments,
+            binding_factors,
+            r,
+            c,
+        })
+    }
+
+    fn pids(&self) -> Vec<u32> {
+        self.commitments
+            .iter()
+            .map(SigningCommitment::pid)
+            .collect()
+    }
+
+    fn position(&self, pid: u32) -> Option<usize> {
+        self.commitments
+            .binary_search_by_key(&pid, SigningCommitment::pid)
+            .ok()
+    }
+
+    fn commitment(&self, pid: u32) -> Option<&SigningCommitment> {
+        self.position(pid)
+            .map(|position| &self.commitments[position])
+    }
+
+    /// Returns the binding factor of a signer that has a commitment.
+    fn binding_factor(&self, pid: u32) -> Scalar {
+        self.binding_factors[self.position(pid).expect("the signer has a commitment")]
+    }
+
+    fn lagrange_coefficient(&self, pid: u32) -> Result<Scalar, ZkError> {
+        vss::lagrange_coefficient(pid, &self.pids())
+    }
+}
+
+/// Returns the domain separation tag `contextString || tag` of the ciphersuite.
+fn dst(tag: &[u8]) -> Vec<u8> {
+    [CONTEXT, tag].concat()
+}
+
+/// Hashes `msg` with SHA-256 under the ciphersuite's tag, `H4` and `H5` of RFC 9591.
+fn h(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(CONTEXT)
+        .chain_update(tag)
+        .chain_update(msg)
+        .finalize()
+        .into()
+}
+
+/// Derives a nonce from `random` and the signer's share, `nonce_generate` of RFC 9591.
+fn nonce(random: &[u8; 32], secret: &SecretScalar) -> SecretScalar {
+    let mut input = Zeroizing::new([0u8; 32 + SCALAR_LEN]);
+    input[..32].copy_from_slice(random);
+    input[32..].copy_from_slice(&secret.expose_secret().to_bytes());
+    SecretScalar::new(hash_to_scalar::<_, Sha256>(input.as_ref(), &dst(b"nonce")))
+}
+
+/// Derives the challenge `c = H2(R || Y || msg)`.
+fn challenge(r: &ProjectivePoint, public_key: &ProjectivePoint, msg: &[u8]) -> Scalar {
+    let mut input = r.to_bytes().to_vec();
+    input.extend_from_slice(&public_key.to_bytes());
+    input.extend_from_slice(msg);
+    hash_to_scalar::<_, Sha256>(&input, &dst(b"chal"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dkg, encoding::hex_to_bytes};
+
+    const G: ProjectivePoint = ProjectivePoint::GENERATOR;
+
+    struct Key {
+        public_key: ProjectivePoint,
+        shares: Vec<SecretShare>,
+    }
+
+    fn key(threshold: usize, parties: u32) -> Key {
+        let secret = SecretScalar::random(&mut rand::thread_rng());
+        let (commitment, _, shares) = vss::share("sid", 0, &secret, threshold, parties).unwrap();
+        Key {
+            public_key: commitment.public_key(),
+            shares,
+        }
+    }
+
+    /// Runs both rounds for the signers at `positions` in `key.shares`.
+    fn round(
+        key: &Key,
+        positions: &[usize],
+        msg: &[u8],
+    ) -> (Vec<SigningCommitment>, Vec<PartialSignature>) {
+        let signers: Vec<_> = positions.iter().map(|&i| &key.shares[i]).collect();
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|share| commit(share)).unzip();
+        let partials = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| sign(share, key.public_key, nonces, msg, &commitments).unwrap())
+            .collect();
+        (commitments, partials)
+    }
+
+    fn verification_share(key: &Key, pid: u32) -> ProjectivePoint {
+        G * key.shares[pid as usize - 1].value().expose_secret()
+    }
+
+    #[test]
+    fn test_sign_every_subset() {
+        let key = key(3, 5);
+        for positions in [[0, 1, 2], [0, 2, 4], [4, 1, 3], [2, 3, 4]] {
+            let (commitments, partials) = round(&key, &positions, b"message");
+            for partial in &partials {
+                assert!(verify_partial(
+                    key.public_key,
+                    verification_share(&key, partial.pid()),
+                    b"message",
+                    &commitments,
+                    partial
+                )
+                .is_ok());
+            }
+            let signature = aggregate(key.public_key, b"message", &commitments, &partials).unwrap();
+            assert!(signature.verify(key.public_key, b"message").is_ok());
+            assert_eq!(
+                signature.verify(key.public_key, b"other"),
+                Err(ZkError::ChallengeMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_more_signers_than_threshold() {
+        let key = key(2, 4);
+        let (commitments, partials) = round(&key, &[0, 1, 2, 3], b"message");
+        let signature = aggregate(key.public_key, b"message", &commitments, &partials).unwrap();
+        assert!(signature.verify(key.public_key, b"message").is_ok());
+    }
+
+    #[test]
+    fn test_fewer_signers_than_threshold() {
+        let key = key(3, 4);
+        let (commitments, partials) = round(&key, &[0, 1], b"message");
+        assert_eq!(
+            aggregate(key.public_key, b"message", &commitments, &partials),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_sign_with_dkg_key() {
+        let (states, commits): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|pid| dkg::commit("sid", pid, 2, 3).unwrap())
+            .unzip();
+        let mut shared = Vec::new();
+        let (mut broadcasts, mut shares) = (Vec::new(), Vec::new());
+        for state in states {
+            let (state, broadcast, messages) = state.share(&commits).unwrap();
+            shared.push(state);
+            broadcasts.push(broadcast);
+            shares.extend(messages);
+        }
+        let (complained, complaints): (Vec<_>, Vec<_>) = shared
+            .into_iter()
+            .map(|state| state.complain(&broadcasts, &shares).unwrap())
+            .unzip();
+        let keys: Vec<_> = complained
+            .into_iter()
+            .map(|state| state.finalize(&complaints, &[]).unwrap())
+            .collect();
+
+        let signers = [&keys[1], &keys[2]];
+        let public_key = keys[0].public_key();
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|key| commit(key.share())).unzip();
+        let partials: Vec<_> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| {
+                sign(key.share(), public_key, nonces, b"message", &commitments).unwrap()
+            })
+            .collect();
+        for partial in &partials {
+            let verification_share = keys[0].verification_share(partial.pid());
+            assert!(verify_partial(
+                public_key,
+                verification_share,
+                b"message",
+                &commitments,
+                partial
+            )
+            .is_ok());
+        }
+        let signature = aggregate(public_key, b"message", &commitments, &partials).unwrap();
+        assert!(signature.verify(public_key, b"message").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_partial_detected() {
+        let key = key(2, 3);
+        let (commitments, mut partials) = round(&key, &[0, 2], b"message");
+        partials[1] = PartialSignature::new(3, partials[1].z() + Scalar::ONE);
+        assert!(verify_partial(
+            key.public_key,
+            verification_share(&key, 1),
+            b"message",
+            &commitments,
+            &partials[0]
+        )
+        .is_ok());
+        assert_eq!(
+            verify_partial(
+                key.public_key,
+                verification_share(&key, 3),
+                b"message",
+                &commitments,
+                &partials[1]
+            ),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            aggregate(key.public_key, b"message", &commitments, &partials),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_binding_factors_bound_to_message_and_commitments() {
+        let key = key(2, 3);
+        let (_, commitments): (Vec<_>, Vec<_>) = key.shares[..2].iter().map(commit).unzip();
+        let signing = Signing::new(key.public_key, b"message", &commitments).unwrap();
+        let other_msg = Signing::new(key.public_key, b"other", &commitments).unwrap();
+        assert_ne!(signing.binding_factors, other_msg.binding_factors);
+
+        // Replacing one signer's commitment changes every signer's binding factor.
+        let (_, replaced) = commit(&key.shares[1]);
+        let other_commitments =
+            Signing::new(key.public_key, b"message", &[commitments[0], replaced]).unwrap();
+        assert_ne!(
+            signing.binding_factor(1),
+            other_commitments.binding_factor(1)
+        );
+
+        // The order of the list does not matter.
+        let reversed = Signing::new(
+            key.public_key,
+            b"message",
+            &[commitments[1], commitments[0]],
+        )
+        .unwrap();
+        assert_eq!(signing.binding_factors, reversed.binding_factors);
+        assert_eq!(signing.c, reversed.c);
+    }
+
+    #[test]
+    fn test_sign_rejects_invalid_commitments() {
+        let key = key(2, 3);
+        let share = &key.shares[0];
+        let (_, other) = commit(&key.shares[1]);
+
+        let (nonces, _) = commit(share);
+        assert!(matches!(
+            sign(share, key.public_key, nonces, b"message", &[other]),
+            Err(ZkError::InvalidInput(_))
+        ));
+
+        // A commitment other than the signer's own nonces.
+        let (nonces, _) = commit(share);
+        let (_, stale) = commit(share);
+        assert!(matches!(
+            sign(share, key.public_key, nonces, b"message", &[stale, other]),
+            Err(ZkError::InvalidInput(_))
+        ));
+
+        let (nonces, commitment) = commit(share);
+        assert!(matches!(
+            sign(
+                share,
+                key.public_key,
+                nonces,
+                b"message",
+                &[commitment, other, other]
+            ),
+            Err(ZkError::InvalidInput(_))
+        ));
+
+        let (nonces, commitment) = commit(share);
+        let identity = SigningCommitment::new(2, ProjectivePoint::IDENTITY, other.binding());
+        assert_eq!(
+            sign(
+                share,
+                key.public_key,
+                nonces,
+                b"message",
+                &[commitment, identity]
+            ),
+            Err(ZkError::IdentityPoint)
+        );
+
+        let (nonces, _) = commit(share);
+        assert!(matches!(
+            sign(share, key.public_key, nonces, b"message", &[]),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_rejects_mismatched_shares() {
+        let key = key(2, 3);
+        let (commitments, partials) = round(&key, &[0, 1], b"message");
+        for partials in [
+            &partials[..1],
+            &[partials[0], partials[0]],
+            &[partials[0], partials[1], partials[1]],
+        ] {
+            assert!(matches!(
+                aggregate(key.public_key, b"message", &commitments, partials),
+                Err(ZkError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_rfc9591_vectors() {
+        // The FROST(secp256k1, SHA-256) vectors of RFC 9591, Appendix E.5.
+        let vectors: serde_json::Value =
+            serde_json::from_str(include_str!("../test_vectors/frost_secp256k1.json")).unwrap();
+        let bytes = |value: &serde_json::Value| hex_to_bytes(value.as_str().unwrap()).unwrap();
+        let inputs = &vectors["inputs"];
+        let public_key =
+            decode_sec1::<k256::Secp256k1>(&bytes(&inputs["verifying_key_key"])).unwrap();
+        let msg = bytes(&inputs["message"]);
+        let shares: Vec<_> = inputs["participant_shares"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|share| {
+                let value = decode_scalar(&bytes(&share["participant_share"])).unwrap();
+                SecretShare::new(
+                    share["identifier"].as_u64().unwrap() as u32,
+                    SecretScalar::new(value),
+                )
+            })
+            .collect();
+
+        let outputs = vectors["round_one_outputs"]["outputs"].as_array().unwrap();
+        let (nonces, commitments): (Vec<_>, Vec<_>) = outputs
+            .iter()
+            .map(|output| {
+                let pid = output["identifier"].as_u64().unwrap() as u32;
+                let random = |key: &str| <[u8; 32]>::try_from(bytes(&output[key])).unwrap();
+                let (nonces, commitment) = commit_with_randomness(
+                    &random("hiding_nonce_randomness"),
+                    &random("binding_nonce_randomness"),
+                    &shares[pid as usize - 1],
+                );
+                assert_eq!(
+                    nonces.hiding.expose_secret().to_bytes().to_vec(),
+                    bytes(&output["hiding_nonce"])
+                );
+                assert_eq!(
+                    nonces.binding.expose_secret().to_bytes().to_vec(),
+                    bytes(&output["binding_nonce"])
+                );
+                assert_eq!(
+                    commitment.hiding.to_bytes().to_vec(),
+                    bytes(&output["hiding_nonce_commitment"])
+                );
+                assert_eq!(
+                    commitment.binding.to_bytes().to_vec(),
+                    bytes(&output["binding_nonce_commitment"])
+                );
+                (nonces, commitment)
+            })
+            .unzip();
+
+        let signing = Signing::new(public_key, &msg, &commitments).unwrap();
+        for output in outputs {
+            let pid = output["identifier"].as_u64().unwrap() as u32;
+            assert_eq!(
+                signing.binding_factor(pid).to_bytes().to_vec(),
+                bytes(&output["binding_factor"])
+            );
+        }
+
+        let partials: Vec<_> = nonces
+            .into_iter()
+            .map(|nonces| {
+                let share = &shares[nonces.commitment.pid as usize - 1];
+                sign(share, public_key, nonces, &msg, &commitments).unwrap()
+            })
+            .collect();
+        for (partial, output) in partials
+            .iter()
+            .zip(vectors["round_two_outputs"]["outputs"].as_array().unwrap())
+        {
+            assert_eq!(
+                u64::from(partial.pid),
+                output["identifier"].as_u64().unwrap()
+            );
+            assert_eq!(partial.z.to_bytes().to_vec(), bytes(&output["sig_share"]));
+        }
+
+        let signature = aggregate(public_key, &msg, &commitments, &partials).unwrap();
+        assert_eq!(
+            signature.to_bytes().to_vec(),
+            bytes(&vectors["final_output"]["sig"])
+        );
+    }
+
+    #[test]
+    fn test_nonce_depends_on_randomness_and_share() {
+        let key = key(2, 3);
+        let random = [7u8; 32];
+        let nonce_1 = nonce(&random, key.shares[0].value());
+        assert_eq!(nonce(&random, key.shares[0].value()), nonce_1);
+        assert_ne!(nonce(&random, key.shares[1].value()), nonce_1);
+        assert_ne!(nonce(&[8u8; 32], key.shares[0].value()), nonce_1);
+    }
+
+    #[test]
+    fn test_signature_bytes() {
+        let key = key(2, 3);
+        let (commitments, partials) = round(&key, &[0, 1], b"message");
+        let signature = aggregate(key.public_key, b"message", &commitments, &partials).unwrap();
+        let bytes = signature.to_bytes();
+        assert_eq!(Signature::from_bytes(&bytes), Ok(signature));
+        assert!(matches!(
+            Signature::from_bytes(&bytes[1..]),
+            Err(ZkError::Encoding(_))
+        ));
+        let mut high = bytes;
+        high[POINT_LEN..].fill(0xff);
+        assert_eq!(
+            Signature::from_bytes(&high),
+            Err(ZkError::NonCanonicalScalar)
+        );
+    }
+}
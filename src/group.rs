@@ -0,0 +1,121 @@
+//! Prime-order groups the proofs in this crate can be instantiated over.
+//!
+//! [`DLogProof`](crate::DLogProof) and [`DLEqProof`](crate::DLEqProof) are generic over a
+//! [`ZkGroup`] and default to secp256k1's [`k256::ProjectivePoint`]. P-256 is available with the
+//! `p256` cargo feature:
+//!
+//! ```
+//! # #[cfg(feature = "p256")]
+//! # {
+//! use p256::ProjectivePoint;
+//! use zk_proof::{DLogProof, SecretScalar};
+//!
+//! let x = SecretScalar::<p256::Scalar>::random(&mut rand::thread_rng());
+//! let y = ProjectivePoint::GENERATOR * x.expose_secret();
+//! let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+//! assert!(dlog_proof.verify("sid", 1, y).is_ok());
+//! # }
+//! ```
+
+use k256::elliptic_curve::{
+    ff::PrimeFieldBits,
+    group::{prime::PrimeGroup, Group, GroupEncoding},
+    zeroize::Zeroize,
+    PrimeField,
+};
+
+use crate::ZkError;
+
+/// A prime-order group with canonical point and scalar encodings.
+///
+/// Points are hashed and serialized with [`GroupEncoding::to_bytes`] and scalars with
+/// [`PrimeField::to_repr`]. The decimal encoding additionally assumes the scalar representation
+/// is big-endian, as it is for every SEC1 curve. Scalars must be zeroizable so they can be held
+/// in a [`SecretScalar`](crate::SecretScalar).
+pub trait ZkGroup: PrimeGroup<Scalar: PrimeFieldBits + Zeroize> {
+    /// Decodes a point received from another party, checking it is a valid group element.
+    ///
+    /// The default implementation accepts exactly the [`GroupEncoding`] representation. Proof
+    /// verification rejects the identity separately.
+    fn decode(bytes: &[u8]) -> Result<Self, ZkError> {
+        let mut repr = Self::Repr::default();
+        if bytes.len() != repr.as_ref().len() {
+            return Err(ZkError::MalformedPoint);
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Option::from(Self::from_bytes(&repr)).ok_or(ZkError::MalformedPoint)
+    }
+}
+
+impl ZkGroup for k256::ProjectivePoint {
+    /// Accepts SEC1 compressed and uncompressed points as well as python-ecdsa's raw 64-byte
+    /// `x || y` encoding.
+    fn decode(bytes: &[u8]) -> Result<Self, ZkError> {
+        crate::encoding::decode_sec1::<k256::Secp256k1>(bytes)
+    }
+}
+
+#[cfg(feature = "p256")]
+impl ZkGroup for p256::ProjectivePoint {
+    /// Accepts SEC1 compressed and uncompressed points as well as the raw 64-byte `x || y`
+    /// encoding.
+    fn decode(bytes: &[u8]) -> Result<Self, ZkError> {
+        crate::encoding::decode_sec1::<p256::NistP256>(bytes)
+    }
+}
+
+/// Returns the length of the encoding of a point of `P`.
+pub(crate) fn point_len<P: GroupEncoding>() -> usize {
+    P::Repr::default().as_ref().len()
+}
+
+/// Returns the length of the encoding of a scalar of `F`.
+pub(crate) fn scalar_len<F: PrimeField>() -> usize {
+    F::Repr::default().as_ref().len()
+}
+
+/// Interprets `bytes` as a big-endian integer and reduces it modulo the field order.
+pub(crate) fn reduce_be_bytes<F: PrimeField>(bytes: &[u8]) -> F {
+    let radix = F::from(256);
+    bytes
+        .iter()
+        .fold(F::ZERO, |acc, byte| acc * radix + F::from(u64::from(*byte)))
+}
+
+/// Rejects statements and proofs involving the point at infinity, which would allow trivial
+/// proofs such as `t = 0`, `s = 0` for `y = 0`.
+pub(crate) fn ensure_non_identity<P: Group>(points: &[P]) -> Result<(), ZkError> {
+    if points.iter().any(|point| bool::from(point.is_identity())) {
+        return Err(ZkError::IdentityPoint);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::{
+        elliptic_curve::{ops::Reduce, sec1::ToEncodedPoint},
+        ProjectivePoint, Scalar, U256,
+    };
+
+    #[test]
+    fn test_reduce_be_bytes_matches_reduce() {
+        for bytes in [[0u8; 32], [0xff; 32], [0x80; 32]] {
+            assert_eq!(
+                reduce_be_bytes::<Scalar>(&bytes),
+                <Scalar as Reduce<U256>>::reduce_bytes(&bytes.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_secp256k1() {
+        let point = ProjectivePoint::GENERATOR * Scalar::from(3u64);
+        assert_eq!(ProjectivePoint::decode(&point.to_bytes()), Ok(point));
+        let uncompressed = point.to_affine().to_encoded_point(false);
+        assert_eq!(ProjectivePoint::decode(uncompressed.as_bytes()), Ok(point));
+        assert_eq!(point_len::<ProjectivePoint>(), 33);
+        assert_eq!(scalar_len::<Scalar>(), 32);
+    }
+}
@@ -0,0 +1,147 @@
+//! Hashing to scalars without modulo bias, over any supported hash function.
+//!
+//! [`hash_to_scalar`] implements `hash_to_field` of RFC 9380 with `expand_message_xmd`: the
+//! message is expanded to `L = ceil((ceil(log2(q)) + 128) / 8)` bytes (48 for 256-bit groups) and
+//! reduced modulo the group order `q`, which leaves a statistical bias of at most `2^-128`.
+//! [`Transcript`](crate::Transcript) challenges are reduced from the same number of bytes.
+//!
+//! Any [`ZkDigest`] can be used, e.g. SHA-256 and SHA-512 from the `sha2` crate, SHA3-256 from
+//! `sha3` or BLAKE2b from `blake2`.
+
+use k256::elliptic_curve::PrimeField;
+use sha2::digest::{core_api::BlockSizeUser, Digest};
+
+use crate::group::reduce_be_bytes;
+
+/// A hash function proofs and transcripts can be instantiated with.
+///
+/// Implemented for every [`Digest`] with a known block size, which covers SHA-2, SHA-3 and
+/// BLAKE2 of the RustCrypto `hashes` crates.
+pub trait ZkDigest: Digest + BlockSizeUser + Clone {}
+
+impl<D: Digest + BlockSizeUser + Clone> ZkDigest for D {}
+
+/// Hashes `msg` to a uniformly distributed scalar with the domain separation tag `dst`, as
+/// `hash_to_field(msg, 1)` of RFC 9380 section 5.2 with `expand_message_xmd`.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::hash::hash_to_scalar;
+/// use k256::Scalar;
+/// use sha2::Sha512;
+///
+/// let c: Scalar = hash_to_scalar::<_, Sha512>(b"message", b"my-protocol-v1");
+/// ```
+pub fn hash_to_scalar<F: PrimeField, D: ZkDigest>(msg: &[u8], dst: &[u8]) -> F {
+    let mut uniform = vec![0u8; wide_len::<F>()];
+    expand_message_xmd::<D>(msg, dst, &mut uniform);
+    reduce_be_bytes(&uniform)
+}
+
+/// Returns the number of uniform bytes reduced to a scalar of `F`, `L` in RFC 9380.
+pub(crate) fn wide_len<F: PrimeField>() -> usize {
+    (F::NUM_BITS as usize + 128).div_ceil(8)
+}
+
+/// Fills `out` with `expand_message_xmd(msg, dst, out.len())` of RFC 9380 section 5.3.1.
+///
+/// Tags longer than 255 bytes are hashed first as described in section 5.3.3.
+///
+/// # Panics
+///
+/// Panics if `out` is longer than 255 digest outputs or 65535 bytes.
+fn expand_message_xmd<D: ZkDigest>(msg: &[u8], dst: &[u8], out: &mut [u8]) {
+    let b_in_bytes = <D as Digest>::output_size();
+    let ell = out.len().div_ceil(b_in_bytes);
+    assert!(
+        ell <= 255 && out.len() <= 0xffff,
+        "requested too many bytes"
+    );
+
+    let oversize;
+    let dst = if dst.len() > 255 {
+        oversize = D::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        oversize.as_slice()
+    } else {
+        dst
+    };
+    let dst_prime = |hasher: D| hasher.chain_update(dst).chain_update([dst.len() as u8]);
+
+    let b_0 = dst_prime(
+        D::new()
+            .chain_update(vec![0u8; D::block_size()])
+            .chain_update(msg)
+            .chain_update((out.len() as u16).to_be_bytes())
+            .chain_update([0]),
+    )
+    .finalize();
+    let mut b_i = dst_prime(D::new().chain_update(&b_0).chain_update([1])).finalize();
+    for (i, chunk) in out.chunks_mut(b_in_bytes).enumerate() {
+        if i > 0 {
+            let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+            b_i = dst_prime(D::new().chain_update(xored).chain_update([i as u8 + 1])).finalize();
+        }
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::{
+        elliptic_curve::hash2curve::{hash_to_field, ExpandMsgXmd},
+        Scalar,
+    };
+    use sha2::{Sha256, Sha512};
+
+    fn expand<D: ZkDigest>(msg: &[u8], dst: &[u8], len: usize) -> String {
+        let mut out = vec![0u8; len];
+        expand_message_xmd::<D>(msg, dst, &mut out);
+        hex::encode(out)
+    }
+
+    #[test]
+    fn test_expand_message_xmd_rfc9380() {
+        // RFC 9380 appendix K.1 and K.2.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            expand::<Sha256>(b"", dst, 0x20),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            expand::<Sha256>(b"abc", dst, 0x20),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        assert_eq!(
+            expand::<Sha512>(b"", dst, 0x20),
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"
+        );
+    }
+
+    #[test]
+    fn test_hash_to_scalar_matches_hash_to_field() {
+        for (msg, dst) in [(&b"abc"[..], &b"dst"[..]), (b"", &[7u8; 300])] {
+            let mut expected = [Scalar::ZERO];
+            hash_to_field::<ExpandMsgXmd<Sha256>, Scalar>(&[msg], &[dst], &mut expected).unwrap();
+            assert_eq!(hash_to_scalar::<Scalar, Sha256>(msg, dst), expected[0]);
+
+            hash_to_field::<ExpandMsgXmd<Sha512>, Scalar>(&[msg], &[dst], &mut expected).unwrap();
+            assert_eq!(hash_to_scalar::<Scalar, Sha512>(msg, dst), expected[0]);
+        }
+    }
+
+    #[test]
+    fn test_other_digests() {
+        let sha3: Scalar = hash_to_scalar::<_, sha3::Sha3_256>(b"abc", b"dst");
+        let blake2: Scalar = hash_to_scalar::<_, blake2::Blake2b512>(b"abc", b"dst");
+        let sha2: Scalar = hash_to_scalar::<_, Sha256>(b"abc", b"dst");
+        assert_ne!(sha3, sha2);
+        assert_ne!(blake2, sha2);
+        assert_eq!(wide_len::<Scalar>(), 48);
+    }
+}
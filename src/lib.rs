@@ -0,0 +1,52 @@
+//! Non-interactive Schnorr ZK DLOG proofs with a Fiat-Shamir transformation over prime-order
+//! groups, secp256k1 by default.
+//!
+//! * [`DLogProof`] proves knowledge of `x` with `y = x*G`; [`CompactDLogProof`] is its compact
+//!   `(c, s)` form.
+//! * [`DLEqProof`] proves that `y = x*G` and `z = x*H` share the same `x`.
+//! * [`OrDLogProof`] proves knowledge of the discrete logarithm of one of `y_1..y_n` without
+//!   revealing which.
+//! * [`PedersenCommitment`] commits to a value, and [`OpeningProof`] proves knowledge of its
+//!   opening.
+//! * [`RangeProof`] proves that committed values lie in `[0, 2^n)` with Bulletproofs.
+//! * [`vss`] splits a secret into Feldman verifiable secret shares.
+//! * [`dkg`] generates a threshold key pair among several parties without a trusted dealer.
+//! * [`frost`] signs with any `t` shares of such a key.
+//! * [`bip340`] signs and verifies BIP-340 Schnorr signatures with x-only keys.
+//! * [`musig2`] aggregates keys and signs n-of-n with MuSig2 into BIP-340 signatures.
+//! * [`sigma`] holds the sigma protocols behind both proofs and composes statements.
+
+pub mod bip340;
+mod compact;
+pub mod compat;
+pub mod dkg;
+mod dleq;
+mod dlog;
+pub mod encoding;
+mod error;
+pub mod frost;
+pub mod group;
+pub mod hash;
+mod msm;
+pub mod musig2;
+mod or;
+mod pedersen;
+mod range;
+mod secret;
+pub mod sigma;
+pub mod transcript;
+pub mod vss;
+
+pub use compact::CompactDLogProof;
+pub use dleq::DLEqProof;
+pub use dlog::{generate_random_number, generate_random_number_with_rng, DLogProof};
+pub use error::ZkError;
+pub use group::ZkGroup;
+pub use k256;
+pub use or::OrDLogProof;
+#[cfg(feature = "p256")]
+pub use p256;
+pub use pedersen::{OpeningProof, PedersenCommitment};
+pub use range::RangeProof;
+pub use secret::SecretScalar;
+pub use transcript::Transcript;
@@ -0,0 +1,89 @@
+use k256::elliptic_curve::{ff::PrimeFieldBits, PrimeField};
+
+use crate::ZkGroup;
+
+/// Computes `sum(scalars[i] * points[i])` with Pippenger's bucket method.
+///
+/// Every window of `c` scalar bits is handled for all points at once, so the doublings are
+/// shared and each point costs about one addition per window instead of a full scalar
+/// multiplication.
+pub(crate) fn msm<P: ZkGroup>(scalars: &[P::Scalar], points: &[P]) -> P {
+    assert_eq!(scalars.len(), points.len(), "msm length mismatch");
+    if points.is_empty() {
+        return P::identity();
+    }
+
+    let c = window_size(points.len());
+    let bits: Vec<_> = scalars.iter().map(|scalar| scalar.to_le_bits()).collect();
+
+    let num_bits = P::Scalar::NUM_BITS as usize;
+    let windows = num_bits.div_ceil(c);
+    let mut buckets = vec![P::identity(); (1 << c) - 1];
+    let mut acc = P::identity();
+    for window in (0..windows).rev() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+
+        buckets.fill(P::identity());
+        for (bits, point) in bits.iter().zip(points) {
+            let end = (window * c + c).min(num_bits);
+            let digit = (window * c..end)
+                .rev()
+                .fold(0, |digit, bit| (digit << 1) | usize::from(bits[bit]));
+            if digit != 0 {
+                buckets[digit - 1] += point;
+            }
+        }
+
+        // sum(d * bucket[d]) via running sums from the top bucket down.
+        let mut running = P::identity();
+        let mut total = P::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            total += running;
+        }
+        acc += total;
+    }
+    acc
+}
+
+fn window_size(n: usize) -> usize {
+    match n {
+        0..=3 => 2,
+        4..=31 => 3,
+        _ => (usize::BITS - n.leading_zeros()).min(16) as usize - 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_random_number;
+    use k256::{ProjectivePoint, Scalar};
+
+    fn naive(scalars: &[Scalar], points: &[ProjectivePoint]) -> ProjectivePoint {
+        scalars
+            .iter()
+            .zip(points)
+            .fold(ProjectivePoint::IDENTITY, |acc, (s, p)| acc + p * s)
+    }
+
+    #[test]
+    fn test_msm_matches_naive() {
+        for n in [0, 1, 2, 5, 40, 100] {
+            let scalars: Vec<Scalar> = (0..n).map(|_| generate_random_number()).collect();
+            let points: Vec<ProjectivePoint> = (0..n)
+                .map(|_| ProjectivePoint::GENERATOR * generate_random_number())
+                .collect();
+            assert_eq!(msm(&scalars, &points), naive(&scalars, &points));
+        }
+    }
+
+    #[test]
+    fn test_msm_edge_scalars() {
+        let scalars = [Scalar::ZERO, Scalar::ONE, -Scalar::ONE];
+        let points = [ProjectivePoint::GENERATOR; 3];
+        assert_eq!(msm(&scalars, &points), ProjectivePoint::IDENTITY);
+    }
+}
@@ -0,0 +1,1241 @@
+//! MuSig2 n-of-n multi-signatures, following BIP-327.
+//!
+//! `n` signers aggregate their public keys into a single x-only key and jointly produce a
+//! [BIP-340](crate::bip340) signature under it, indistinguishable from a single-signer one:
+//!
+//! 1. **Key aggregation** ([`KeyAggContext::new`]): every key `P_i` is weighted with the
+//!    coefficient `a_i = hash_KeyAgg coefficient(L || P_i)`, where `L` hashes the whole key
+//!    list, into `Q = sum(a_i*P_i)`. The coefficients prevent rogue-key attacks. The aggregate
+//!    key can be tweaked, e.g. for Taproot, with [`KeyAggContext::apply_tweak`].
+//! 2. **Nonce round** ([`nonce_gen`]): every signer draws two nonces, keeps the
+//!    [`SecretNonce`] and sends the [`PublicNonce`]. Any party sums the public nonces with
+//!    [`aggregate_nonces`]. This round does not depend on the message and can be run ahead.
+//! 3. **Signing round** ([`Session::sign`]): every signer combines its two nonces with a
+//!    coefficient `b` bound to the message and the aggregate nonce and sends its
+//!    [`PartialSignature`]. Partial signatures are checked with [`Session::verify_partial`] and
+//!    summed with [`Session::aggregate`].
+//!
+//! Keys are handled as points and encoded as 33-byte compressed SEC1 points where BIP-327 hashes
+//! them. Nonce generation, nonce aggregation, tweaking and partial signing reproduce the BIP-327
+//! test vectors.
+//!
+//! ```
+//! use zk_proof::{musig2, SecretScalar};
+//! use k256::ProjectivePoint;
+//!
+//! let secrets: Vec<_> = (0..3)
+//!     .map(|_| SecretScalar::random(&mut rand::thread_rng()))
+//!     .collect();
+//! let public_keys: Vec<_> = secrets
+//!     .iter()
+//!     .map(|secret| ProjectivePoint::GENERATOR * secret.expose_secret())
+//!     .collect();
+//! let key_agg = musig2::KeyAggContext::new(&musig2::key_sort(&public_keys)).unwrap();
+//!
+//! let msg = b"message";
+//! let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) = secrets
+//!     .iter()
+//!     .map(|secret| musig2::nonce_gen(secret, &key_agg, Some(msg)).unwrap())
+//!     .unzip();
+//! let aggregate_nonce = musig2::aggregate_nonces(&public_nonces).unwrap();
+//!
+//! let session = musig2::Session::new(&key_agg, &aggregate_nonce, msg);
+//! let partials: Vec<_> = secrets
+//!     .iter()
+//!     .zip(secret_nonces)
+//!     .map(|(secret, nonce)| session.sign(nonce, secret).unwrap())
+//!     .collect();
+//! for ((partial, nonce), public_key) in partials.iter().zip(&public_nonces).zip(&public_keys) {
+//!     assert!(session.verify_partial(partial, nonce, *public_key).is_ok());
+//! }
+//!
+//! let signature = session.aggregate(&partials).unwrap();
+//! assert!(signature.verify(key_agg.x_only_public_key(), msg).is_ok());
+//! ```
+
+use k256::{
+    elliptic_curve::{
+        group::{Group, GroupEncoding},
+        rand_core::CryptoRngCore,
+        zeroize::Zeroizing,
+    },
+    ProjectivePoint, Scalar,
+};
+
+use crate::{
+    bip340::{self, challenge, even_y, reduce, tagged_hash, x_bytes, XOnlyPublicKey},
+    encoding::{decode_scalar, decode_sec1},
+    group::ensure_non_identity,
+    SecretScalar, ZkError,
+};
+
+const POINT_LEN: usize = 33;
+
+/// Sorts public keys by their compressed encoding, `KeySort` of BIP-327.
+///
+/// Signers that agree on a set of keys but not on an order sort them before aggregation.
+pub fn key_sort(public_keys: &[ProjectivePoint]) -> Vec<ProjectivePoint> {
+    let mut sorted = public_keys.to_vec();
+    sorted.sort_by_cached_key(cbytes);
+    sorted
+}
+
+/// The aggregate of a list of public keys, with the tweaks applied to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggContext {
+    public_keys: Vec<ProjectivePoint>,
+    /// The hash `L` of the key list.
+    list_hash: [u8; 32],
+    /// The first key that differs from the first one, whose coefficient is 1, or zero bytes.
+    second_key: [u8; POINT_LEN],
+    /// The aggregate key `Q`.
+    q: ProjectivePoint,
+    /// The accumulated sign `g` of the x-only tweaks.
+    gacc: Scalar,
+    /// The accumulated tweak `t`.
+    tacc: Scalar,
+}
+
+impl KeyAggContext {
+    /// Aggregates `public_keys` into `Q = sum(a_i*P_i)`, `KeyAgg` of BIP-327.
+    ///
+    /// The aggregate depends on the order of the keys; see [`key_sort`]. A key may appear more
+    /// than once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `public_keys` is empty and
+    /// [`ZkError::IdentityPoint`] if a key or the aggregate is the point at infinity.
+    pub fn new(public_keys: &[ProjectivePoint]) -> Result<Self, ZkError> {
+        let Some(first) = public_keys.first() else {
+            return Err(ZkError::InvalidInput("no public keys".to_string()));
+        };
+        ensure_non_identity(public_keys)?;
+        let encoded: Vec<_> = public_keys.iter().map(cbytes).collect();
+        let parts: Vec<&[u8]> = encoded.iter().map(|pk| pk.as_slice()).collect();
+        let first = cbytes(first);
+        let mut context = Self {
+            public_keys: public_keys.to_vec(),
+            list_hash: tagged_hash(b"KeyAgg list", &parts),
+            second_key: encoded
+                .iter()
+                .find(|pk| **pk != first)
+                .copied()
+                .unwrap_or([0; POINT_LEN]),
+            q: ProjectivePoint::IDENTITY,
+            gacc: Scalar::ONE,
+            tacc: Scalar::ZERO,
+        };
+        context.q = public_keys
+            .iter()
+            .zip(&encoded)
+            .map(|(point, pk)| *point * context.coefficient(pk))
+            .sum();
+        ensure_non_identity(&[context.q])?;
+        Ok(context)
+    }
+
+    /// Returns the aggregated public keys, in order.
+    pub fn public_keys(&self) -> &[ProjectivePoint] {
+        &self.public_keys
+    }
+
+    /// Returns the aggregate key `Q` with its y coordinate, including the tweaks.
+    pub fn aggregate_public_key(&self) -> ProjectivePoint {
+        self.q
+    }
+
+    /// Returns the x-only key the aggregate signatures verify under.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        XOnlyPublicKey::new(self.q).expect("the aggregate key is not the point at infinity")
+    }
+
+    /// Returns the key aggregation coefficient `a_i` of `public_key`, or `None` if it is not
+    /// among the aggregated keys.
+    pub fn key_agg_coefficient(&self, public_key: ProjectivePoint) -> Option<Scalar> {
+        self.public_keys
+            .contains(&public_key)
+            .then(|| self.coefficient(&cbytes(&public_key)))
+    }
+
+    /// Adds `tweak*G` to the aggregate key, `ApplyTweak` of BIP-327.
+    ///
+    /// With `x_only`, the tweak is added to the x-only key, i.e. to `Q` negated to an even y
+    /// coordinate, as for Taproot output keys; otherwise it is added to `Q` itself, as for
+    /// BIP-32 derivation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if the tweaked key is the point at infinity.
+    pub fn apply_tweak(mut self, tweak: Scalar, x_only: bool) -> Result<Self, ZkError> {
+        let g = if x_only && !has_even_y(&self.q) {
+            -Scalar::ONE
+        } else {
+            Scalar::ONE
+        };
+        let q = self.q * g + ProjectivePoint::GENERATOR * tweak;
+        ensure_non_identity(&[q])?;
+        self.q = q;
+        self.gacc *= g;
+        self.tacc = tweak + g * self.tacc;
+        Ok(self)
+    }
+
+    fn coefficient(&self, public_key: &[u8; POINT_LEN]) -> Scalar {
+        if *public_key == self.second_key {
+            return Scalar::ONE;
+        }
+        reduce(&tagged_hash(
+            b"KeyAgg coefficient",
+            &[&self.list_hash, public_key],
+        ))
+    }
+
+    /// Returns the sign `g*gacc` the signers' keys are multiplied with.
+    fn key_sign(&self) -> Scalar {
+        if has_even_y(&self.q) {
+            self.gacc
+        } else {
+            -self.gacc
+        }
+    }
+}
+
+/// The two secret nonces `k_1`, `k_2` of a signer and the public key they were drawn for.
+///
+/// The nonces are wiped when dropped and cannot be cloned, so that they sign only once.
+#[derive(Debug)]
+pub struct SecretNonce {
+    k1: SecretScalar,
+    k2: SecretScalar,
+    public_key: ProjectivePoint,
+}
+
+/// The public nonces `R_1 = k_1*G` and `R_2 = k_2*G` of a signer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicNonce {
+    r1: ProjectivePoint,
+    r2: ProjectivePoint,
+}
+
+impl PublicNonce {
+    /// Creates a public nonce from its two points.
+    pub fn new(r1: ProjectivePoint, r2: ProjectivePoint) -> Self {
+        Self { r1, r2 }
+    }
+
+    /// Returns `R_1`.
+    pub fn r1(&self) -> ProjectivePoint {
+        self.r1
+    }
+
+    /// Returns `R_2`.
+    pub fn r2(&self) -> ProjectivePoint {
+        self.r2
+    }
+
+    /// Encodes the nonce as the compressed `R_1` followed by the compressed `R_2`.
+    pub fn to_bytes(&self) -> [u8; 2 * POINT_LEN] {
+        let mut bytes = [0u8; 2 * POINT_LEN];
+        bytes[..POINT_LEN].copy_from_slice(&cbytes(&self.r1));
+        bytes[POINT_LEN..].copy_from_slice(&cbytes(&self.r2));
+        bytes
+    }
+
+    /// Decodes a nonce encoded with [`PublicNonce::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `bytes` is not 66 bytes long and
+    /// [`ZkError::MalformedPoint`] for invalid points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        check_len(bytes, 2 * POINT_LEN, "nonce")?;
+        Ok(Self::new(
+            decode_sec1::<k256::Secp256k1>(&bytes[..POINT_LEN])?,
+            decode_sec1::<k256::Secp256k1>(&bytes[POINT_LEN..])?,
+        ))
+    }
+}
+
+/// The sums `R_1`, `R_2` of the signers' public nonces, either of which may be the point at
+/// infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregateNonce {
+    r1: ProjectivePoint,
+    r2: ProjectivePoint,
+}
+
+impl AggregateNonce {
+    /// Creates an aggregate nonce from its two points.
+    pub fn new(r1: ProjectivePoint, r2: ProjectivePoint) -> Self {
+        Self { r1, r2 }
+    }
+
+    /// Returns `R_1`.
+    pub fn r1(&self) -> ProjectivePoint {
+        self.r1
+    }
+
+    /// Returns `R_2`.
+    pub fn r2(&self) -> ProjectivePoint {
+        self.r2
+    }
+
+    /// Encodes the nonce like [`PublicNonce::to_bytes`], with 33 zero bytes for the point at
+    /// infinity.
+    pub fn to_bytes(&self) -> [u8; 2 * POINT_LEN] {
+        let mut bytes = [0u8; 2 * POINT_LEN];
+        bytes[..POINT_LEN].copy_from_slice(&cbytes_ext(&self.r1));
+        bytes[POINT_LEN..].copy_from_slice(&cbytes_ext(&self.r2));
+        bytes
+    }
+
+    /// Decodes a nonce encoded with [`AggregateNonce::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `bytes` is not 66 bytes long and
+    /// [`ZkError::MalformedPoint`] for invalid points.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        check_len(bytes, 2 * POINT_LEN, "nonce")?;
+        Ok(Self::new(
+            cpoint_ext(&bytes[..POINT_LEN])?,
+            cpoint_ext(&bytes[POINT_LEN..])?,
+        ))
+    }
+}
+
+/// The partial signature `s_i` of one signer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature(Scalar);
+
+impl PartialSignature {
+    /// Creates a partial signature from its scalar.
+    pub fn new(s: Scalar) -> Self {
+        Self(s)
+    }
+
+    /// Returns `s_i`.
+    pub fn s(&self) -> &Scalar {
+        &self.0
+    }
+
+    /// Encodes the partial signature as the 32-byte big-endian `s_i`.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes().into()
+    }
+
+    /// Decodes a partial signature encoded with [`PartialSignature::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `bytes` is not 32 bytes long and
+    /// [`ZkError::NonCanonicalScalar`] if `s_i` is not smaller than the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        decode_scalar(bytes).map(Self)
+    }
+}
+
+/// Draws the nonces of the signer with the secret key `d` for a signature under `key_agg`,
+/// drawing the randomness from the thread-local generator.
+///
+/// `msg` may be left out to run the nonce round before the message is known.
+///
+/// # Returns
+///
+/// The nonce to keep until [`Session::sign`] and the [`PublicNonce`] to send to the other
+/// signers.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `d` is zero or, with negligible probability, a nonce is
+/// zero.
+pub fn nonce_gen(
+    secret: &SecretScalar,
+    key_agg: &KeyAggContext,
+    msg: Option<&[u8]>,
+) -> Result<(SecretNonce, PublicNonce), ZkError> {
+    nonce_gen_with_rng(&mut rand::thread_rng(), secret, key_agg, msg)
+}
+
+/// Draws the nonces like [`nonce_gen`] from `rng`.
+///
+/// As in `NonceGen` of BIP-327, each nonce hashes 32 random bytes together with the secret key,
+/// the keys and the message, so that a weak `rng` alone does not reveal it.
+pub fn nonce_gen_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    secret: &SecretScalar,
+    key_agg: &KeyAggContext,
+    msg: Option<&[u8]>,
+) -> Result<(SecretNonce, PublicNonce), ZkError> {
+    if bool::from(secret.expose_secret().is_zero()) {
+        return Err(ZkError::InvalidInput("the secret key is zero".to_string()));
+    }
+    let mut random = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(random.as_mut());
+    let public_key = ProjectivePoint::GENERATOR * secret.expose_secret();
+    let aggregate_key = key_agg.x_only_public_key().to_bytes();
+    nonce_gen_internal(&random, Some(secret), public_key, &aggregate_key, msg, &[])
+}
+
+/// `NonceGen` of BIP-327 for the random bytes `rand'`, with an empty `aggregate_key` or
+/// `extra_in` standing for an absent one.
+fn nonce_gen_internal(
+    random: &[u8; 32],
+    secret: Option<&SecretScalar>,
+    public_key: ProjectivePoint,
+    aggregate_key: &[u8],
+    msg: Option<&[u8]>,
+    extra_in: &[u8],
+) -> Result<(SecretNonce, PublicNonce), ZkError> {
+    let mut rand = Zeroizing::new(*random);
+    if let Some(secret) = secret {
+        rand.copy_from_slice(&secret.expose_secret().to_bytes());
+        for (byte, mask) in rand.iter_mut().zip(tagged_hash(b"MuSig/aux", &[random])) {
+            *byte ^= mask;
+        }
+    }
+    let encoded_key = cbytes(&public_key);
+    let msg_prefixed = match msg {
+        Some(msg) => [&[1][..], &(msg.len() as u64).to_be_bytes(), msg].concat(),
+        None => vec![0],
+    };
+    let k = |i: u8| {
+        SecretScalar::new(reduce(&tagged_hash(
+            b"MuSig/nonce",
+            &[
+                rand.as_slice(),
+                &[POINT_LEN as u8],
+                &encoded_key,
+                &[aggregate_key.len() as u8],
+                aggregate_key,
+                &msg_prefixed,
+                &(extra_in.len() as u32).to_be_bytes(),
+                extra_in,
+                &[i],
+            ],
+        )))
+    };
+    let (k1, k2) = (k(0), k(1));
+    if bool::from(k1.expose_secret().is_zero() | k2.expose_secret().is_zero()) {
+        return Err(ZkError::InvalidInput("a nonce is zero".to_string()));
+    }
+    let public_nonce = PublicNonce::new(
+        ProjectivePoint::GENERATOR * k1.expose_secret(),
+        ProjectivePoint::GENERATOR * k2.expose_secret(),
+    );
+    let secret_nonce = SecretNonce { k1, k2, public_key };
+    Ok((secret_nonce, public_nonce))
+}
+
+/// Sums the public nonces of all signers, `NonceAgg` of BIP-327.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `nonces` is empty and [`ZkError::IdentityPoint`] if a
+/// nonce point is the point at infinity.
+pub fn aggregate_nonces(nonces: &[PublicNonce]) -> Result<AggregateNonce, ZkError> {
+    if nonces.is_empty() {
+        return Err(ZkError::InvalidInput("no nonces".to_string()));
+    }
+    for nonce in nonces {
+        ensure_non_identity(&[nonce.r1, nonce.r2])?;
+    }
+    Ok(AggregateNonce::new(
+        nonces.iter().map(|nonce| nonce.r1).sum(),
+        nonces.iter().map(|nonce| nonce.r2).sum(),
+    ))
+}
+
+/// The values every signer derives from the aggregate key, the aggregate nonce and the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    key_agg: KeyAggContext,
+    /// The nonce coefficient `b`.
+    b: Scalar,
+    /// The final nonce `R = R_1 + b*R_2`, or `G` if that is the point at infinity.
+    r: ProjectivePoint,
+    /// The BIP-340 challenge `e`.
+    e: Scalar,
+    msg: Vec<u8>,
+}
+
+impl Session {
+    /// Starts the signing of `msg` under `key_agg` with the aggregate nonce of the signers,
+    /// `GetSessionValues` of BIP-327.
+    pub fn new(key_agg: &KeyAggContext, aggregate_nonce: &AggregateNonce, msg: &[u8]) -> Self {
+        let q = key_agg.x_only_public_key().to_bytes();
+        let b = reduce(&tagged_hash(
+            b"MuSig/noncecoef",
+            &[&aggregate_nonce.to_bytes(), &q, msg],
+        ));
+        let r = aggregate_nonce.r1 + aggregate_nonce.r2 * b;
+        let r = if bool::from(r.is_identity()) {
+            ProjectivePoint::GENERATOR
+        } else {
+            r
+        };
+        let e = challenge(&x_bytes(&r), &q, msg);
+        Self {
+            key_agg: key_agg.clone(),
+            b,
+            r,
+            e,
+            msg: msg.to_vec(),
+        }
+    }
+
+    /// Computes the partial signature `s_i = k_1 + b*k_2 + e*a_i*d_i` of the signer with the
+    /// secret key `d_i`, with the nonces and the key negated as the even y coordinates of `R`
+    /// and `Q` require.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if the nonce was drawn for another key or is zero, or
+    /// the signer's key is not among the aggregated keys.
+    pub fn sign(
+        &self,
+        nonce: SecretNonce,
+        secret: &SecretScalar,
+    ) -> Result<PartialSignature, ZkError> {
+        let public_key = ProjectivePoint::GENERATOR * secret.expose_secret();
+        if nonce.public_key != public_key {
+            return Err(ZkError::InvalidInput(
+                "the nonce was drawn for another key".to_string(),
+            ));
+        }
+        if bool::from(nonce.k1.expose_secret().is_zero() | nonce.k2.expose_secret().is_zero()) {
+            return Err(ZkError::InvalidInput("the nonce is zero".to_string()));
+        }
+        let a = self.coefficient(public_key)?;
+        let (k1, k2) = if has_even_y(&self.r) {
+            (*nonce.k1.expose_secret(), *nonce.k2.expose_secret())
+        } else {
+            (-nonce.k1.expose_secret(), -nonce.k2.expose_secret())
+        };
+        let d = SecretScalar::new(self.key_agg.key_sign() * secret.expose_secret());
+        Ok(PartialSignature::new(
+            k1 + self.b * k2 + self.e * a * d.expose_secret(),
+        ))
+    }
+
+    /// Verifies the partial signature of the signer with the key `P_i` and the public nonce
+    /// `(R_1, R_2)`, `PartialSigVerifyInternal` of BIP-327.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `public_key` is not among the aggregated keys and
+    /// [`ZkError::ChallengeMismatch`] if the partial signature does not hold.
+    pub fn verify_partial(
+        &self,
+        partial: &PartialSignature,
+        nonce: &PublicNonce,
+        public_key: ProjectivePoint,
+    ) -> Result<(), ZkError> {
+        let a = self.coefficient(public_key)?;
+        let r = nonce.r1 + nonce.r2 * self.b;
+        let r = if has_even_y(&self.r) { r } else { -r };
+        let g = self.key_agg.key_sign();
+        if ProjectivePoint::GENERATOR * partial.0 != r + public_key * (self.e * a * g) {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Sums the partial signatures of all signers into the BIP-340 signature, adding the tweaks,
+    /// and verifies it under the aggregate key, `PartialSigAgg` of BIP-327.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::ChallengeMismatch`] if the signature does not hold; the faulty
+    /// partial signatures are then found with [`Session::verify_partial`].
+    pub fn aggregate(&self, partials: &[PartialSignature]) -> Result<bip340::Signature, ZkError> {
+        let g = if has_even_y(&self.key_agg.q) {
+            Scalar::ONE
+        } else {
+            -Scalar::ONE
+        };
+        let s = partials.iter().map(|partial| partial.0).sum::<Scalar>()
+            + self.e * g * self.key_agg.tacc;
+        let signature = bip340::Signature::new(x_bytes(&self.r), s);
+        signature.verify(self.key_agg.x_only_public_key(), &self.msg)?;
+        Ok(signature)
+    }
+
+    fn coefficient(&self, public_key: ProjectivePoint) -> Result<Scalar, ZkError> {
+        self.key_agg
+            .key_agg_coefficient(public_key)
+            .ok_or_else(|| ZkError::InvalidInput("the key is not among the signers".to_string()))
+    }
+}
+
+fn has_even_y(point: &ProjectivePoint) -> bool {
+    !even_y(*point).1
+}
+
+fn cbytes(point: &ProjectivePoint) -> [u8; POINT_LEN] {
+    let mut bytes = [0u8; POINT_LEN];
+    bytes.copy_from_slice(&point.to_bytes());
+    bytes
+}
+
+/// Encodes `point` like [`cbytes`], with zero bytes for the point at infinity.
+fn cbytes_ext(point: &ProjectivePoint) -> [u8; POINT_LEN] {
+    if bool::from(point.is_identity()) {
+        [0; POINT_LEN]
+    } else {
+        cbytes(point)
+    }
+}
+
+/// Decodes a point encoded with [`cbytes_ext`].
+fn cpoint_ext(bytes: &[u8]) -> Result<ProjectivePoint, ZkError> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(ProjectivePoint::IDENTITY);
+    }
+    decode_sec1::<k256::Secp256k1>(bytes)
+}
+
+fn check_len(bytes: &[u8], len: usize, what: &str) -> Result<(), ZkError> {
+    if bytes.len() != len {
+        return Err(ZkError::Encoding(format!(
+            "expected {} {} bytes, got {}",
+            len,
+            what,
+            bytes.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+    use serde_json::Value;
+
+    fn point(hex: &str) -> ProjectivePoint {
+        decode_sec1::<k256::Secp256k1>(&hex_to_bytes(hex).unwrap()).unwrap()
+    }
+
+    fn bytes(value: &Value) -> Vec<u8> {
+        hex_to_bytes(value.as_str().unwrap()).unwrap()
+    }
+
+    /// Returns the entries of the hex array `values` at the positions of the index array
+    /// `indices`.
+    fn select(values: &Value, indices: &Value) -> Vec<Vec<u8>> {
+        indices
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|index| bytes(&values[index.as_u64().unwrap() as usize]))
+            .collect()
+    }
+
+    fn points(encoded: &[Vec<u8>]) -> Result<Vec<ProjectivePoint>, ZkError> {
+        encoded
+            .iter()
+            .map(|bytes| decode_sec1::<k256::Secp256k1>(bytes))
+            .collect()
+    }
+
+    /// Decodes the 97-byte `k_1 || k_2 || P` secret nonce of BIP-327.
+    fn secret_nonce(bytes: &[u8]) -> SecretNonce {
+        SecretNonce {
+            k1: SecretScalar::new(decode_scalar(&bytes[..32]).unwrap()),
+            k2: SecretScalar::new(decode_scalar(&bytes[32..64]).unwrap()),
+            public_key: decode_sec1::<k256::Secp256k1>(&bytes[64..]).unwrap(),
+        }
+    }
+
+    struct Signers {
+        secrets: Vec<SecretScalar>,
+        public_keys: Vec<ProjectivePoint>,
+    }
+
+    fn signers(n: usize) -> Signers {
+        let secrets: Vec<_> = (0..n)
+            .map(|_| SecretScalar::random(&mut rand::thread_rng()))
+            .collect();
+        let public_keys = secrets
+            .iter()
+            .map(|secret| ProjectivePoint::GENERATOR * secret.expose_secret())
+            .collect();
+        Signers {
+            secrets,
+            public_keys,
+        }
+    }
+
+    /// Runs both rounds and returns the session, the public nonces and the partial signatures.
+    fn run(
+        signers: &Signers,
+        key_agg: &KeyAggContext,
+        msg: &[u8],
+    ) -> (Session, Vec<PublicNonce>, Vec<PartialSignature>) {
+        let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) = signers
+            .secrets
+            .iter()
+            .map(|secret| nonce_gen(secret, key_agg, None).unwrap())
+            .unzip();
+        let session = Session::new(key_agg, &aggregate_nonces(&public_nonces).unwrap(), msg);
+        let partials = signers
+            .secrets
+            .iter()
+            .zip(secret_nonces)
+            .map(|(secret, nonce)| session.sign(nonce, secret).unwrap())
+            .collect();
+        (session, public_nonces, partials)
+    }
+
+    #[test]
+    fn test_key_agg_vectors() {
+        // The valid cases of key_agg_vectors.json of BIP-327.
+        let keys = [
+            point("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            point("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"),
+            point("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66"),
+        ];
+        for (indices, expected) in [
+            (
+                &[0, 1, 2][..],
+                "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C",
+            ),
+            (
+                &[2, 1, 0],
+                "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B",
+            ),
+            (
+                &[0, 0, 0],
+                "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935",
+            ),
+            (
+                &[0, 0, 1, 1],
+                "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E",
+            ),
+        ] {
+            let public_keys: Vec<_> = indices.iter().map(|&i| keys[i]).collect();
+            let key_agg = KeyAggContext::new(&public_keys).unwrap();
+            assert_eq!(
+                key_agg.x_only_public_key().to_bytes().to_vec(),
+                hex_to_bytes(expected).unwrap(),
+                "keys {:?}",
+                indices
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonce_gen_vectors() {
+        // The cases of nonce_gen_vectors.json of BIP-327 that libsecp256k1 carries.
+        let vectors: Value = serde_json::from_str(include_str!(
+            "../test_vectors/musig2_nonce_gen_vectors.json"
+        ))
+        .unwrap();
+        for case in vectors["test_cases"].as_array().unwrap() {
+            let optional = |key: &str| (!case[key].is_null()).then(|| bytes(&case[key]));
+            let secret = optional("sk").map(|sk| SecretScalar::new(decode_scalar(&sk).unwrap()));
+            let msg = optional("msg");
+            let (secret_nonce, public_nonce) = nonce_gen_internal(
+                &bytes(&case["rand_"]).try_into().unwrap(),
+                secret.as_ref(),
+                point(case["pk"].as_str().unwrap()),
+                &optional("aggpk").unwrap_or_default(),
+                msg.as_deref(),
+                &optional("extra_in").unwrap_or_default(),
+            )
+            .unwrap();
+            let encoded = [
+                secret_nonce.k1.expose_secret().to_bytes().as_slice(),
+                &secret_nonce.k2.expose_secret().to_bytes(),
+                &cbytes(&secret_nonce.public_key),
+            ]
+            .concat();
+            assert_eq!(encoded, bytes(&case["expected_secnonce"]));
+            assert_eq!(
+                public_nonce.to_bytes().to_vec(),
+                bytes(&case["expected_pubnonce"])
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonce_agg_vectors() {
+        let vectors: Value = serde_json::from_str(include_str!(
+            "../test_vectors/musig2_nonce_agg_vectors.json"
+        ))
+        .unwrap();
+        let pnonces = &vectors["pnonces"];
+        for case in vectors["valid_test_cases"].as_array().unwrap() {
+            let nonces: Vec<_> = select(pnonces, &case["pnonce_indices"])
+                .iter()
+                .map(|bytes| PublicNonce::from_bytes(bytes).unwrap())
+                .collect();
+            let aggregate_nonce = aggregate_nonces(&nonces).unwrap();
+            assert_eq!(
+                aggregate_nonce.to_bytes().to_vec(),
+                bytes(&case["expected"])
+            );
+        }
+        for case in vectors["error_test_cases"].as_array().unwrap() {
+            let signer = select(pnonces, &case["pnonce_indices"])
+                .iter()
+                .position(|bytes| PublicNonce::from_bytes(bytes).is_err());
+            assert_eq!(
+                signer,
+                Some(case["error"]["signer"].as_u64().unwrap() as usize),
+                "{}",
+                case["comment"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_verify_vectors() {
+        let vectors: Value = serde_json::from_str(include_str!(
+            "../test_vectors/musig2_sign_verify_vectors.json"
+        ))
+        .unwrap();
+        let secret = SecretScalar::new(decode_scalar(&bytes(&vectors["sk"])).unwrap());
+        let (pubkeys, pnonces, aggnonces, msgs) = (
+            &vectors["pubkeys"],
+            &vectors["pnonces"],
+            &vectors["aggnonces"],
+            &vectors["msgs"],
+        );
+        let secnonce = |index: &Value| {
+            secret_nonce(&bytes(
+                &vectors["secnonces"][index.as_u64().unwrap() as usize],
+            ))
+        };
+        let index = |value: &Value| value.as_u64().unwrap() as usize;
+
+        for case in vectors["valid_test_cases"].as_array().unwrap() {
+            let public_keys = points(&select(pubkeys, &case["key_indices"])).unwrap();
+            let nonces: Vec<_> = select(pnonces, &case["nonce_indices"])
+                .iter()
+                .map(|bytes| PublicNonce::from_bytes(bytes).unwrap())
+                .collect();
+            let aggregate_nonce =
+                AggregateNonce::from_bytes(&bytes(&aggnonces[index(&case["aggnonce_index"])]))
+                    .unwrap();
+            assert_eq!(aggregate_nonces(&nonces), Ok(aggregate_nonce));
+            let key_agg = KeyAggContext::new(&public_keys).unwrap();
+            let session = Session::new(
+                &key_agg,
+                &aggregate_nonce,
+                &bytes(&msgs[index(&case["msg_index"])]),
+            );
+            let partial = session.sign(secnonce(&0.into()), &secret).unwrap();
+            assert_eq!(partial.to_bytes().to_vec(), bytes(&case["expected"]));
+            let signer = index(&case["signer_index"]);
+            assert!(session
+                .verify_partial(&partial, &nonces[signer], public_keys[signer])
+                .is_ok());
+        }
+
+        for case in vectors["sign_error_test_cases"].as_array().unwrap() {
+            let public_keys = match points(&select(pubkeys, &case["key_indices"])) {
+                Ok(public_keys) => public_keys,
+                Err(error) => {
+                    assert_eq!(case["error"]["contrib"], "pubkey");
+                    assert_eq!(error, ZkError::MalformedPoint);
+                    continue;
+                }
+            };
+            let aggregate_nonce = match AggregateNonce::from_bytes(&bytes(
+                &aggnonces[index(&case["aggnonce_index"])],
+            )) {
+                Ok(aggregate_nonce) => aggregate_nonce,
+                Err(error) => {
+                    assert_eq!(case["error"]["contrib"], "aggnonce");
+                    assert_eq!(error, ZkError::MalformedPoint);
+                    continue;
+                }
+            };
+            let key_agg = KeyAggContext::new(&public_keys).unwrap();
+            let session = Session::new(
+                &key_agg,
+                &aggregate_nonce,
+                &bytes(&msgs[index(&case["msg_index"])]),
+            );
+            assert!(
+                matches!(
+                    session.sign(secnonce(&case["secnonce_index"]), &secret),
+                    Err(ZkError::InvalidInput(_))
+                ),
+                "{}",
+                case["comment"]
+            );
+        }
+
+        for case in vectors["verify_fail_test_cases"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .chain(vectors["verify_error_test_cases"].as_array().unwrap())
+        {
+            let verified = || {
+                let public_keys = points(&select(pubkeys, &case["key_indices"]))?;
+                let nonces = select(pnonces, &case["nonce_indices"])
+                    .iter()
+                    .map(|bytes| PublicNonce::from_bytes(bytes))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let partial = PartialSignature::from_bytes(&bytes(&case["sig"]))?;
+                let key_agg = KeyAggContext::new(&public_keys)?;
+                let session = Session::new(
+                    &key_agg,
+                    &aggregate_nonces(&nonces)?,
+                    &bytes(&msgs[index(&case["msg_index"])]),
+                );
+                let signer = index(&case["signer_index"]);
+                session.verify_partial(&partial, &nonces[signer], public_keys[signer])
+            };
+            assert!(verified().is_err(), "{}", case["comment"]);
+        }
+    }
+
+    #[test]
+    fn test_tweak_vectors() {
+        let vectors: Value =
+            serde_json::from_str(include_str!("../test_vectors/musig2_tweak_vectors.json"))
+                .unwrap();
+        let secret = SecretScalar::new(decode_scalar(&bytes(&vectors["sk"])).unwrap());
+        let aggregate_nonce = AggregateNonce::from_bytes(&bytes(&vectors["aggnonce"])).unwrap();
+        let msg = bytes(&vectors["msg"]);
+        let tweaks = &vectors["tweaks"];
+
+        for case in vectors["valid_test_cases"].as_array().unwrap() {
+            let public_keys = points(&select(&vectors["pubkeys"], &case["key_indices"])).unwrap();
+            let nonces: Vec<_> = select(&vectors["pnonces"], &case["nonce_indices"])
+                .iter()
+                .map(|bytes| PublicNonce::from_bytes(bytes).unwrap())
+                .collect();
+            assert_eq!(aggregate_nonces(&nonces), Ok(aggregate_nonce));
+            let mut key_agg = KeyAggContext::new(&public_keys).unwrap();
+            for (tweak, x_only) in select(tweaks, &case["tweak_indices"])
+                .iter()
+                .zip(case["is_xonly"].as_array().unwrap())
+            {
+                key_agg = key_agg
+                    .apply_tweak(decode_scalar(tweak).unwrap(), x_only.as_bool().unwrap())
+                    .unwrap();
+            }
+            let session = Session::new(&key_agg, &aggregate_nonce, &msg);
+            let partial = session
+                .sign(secret_nonce(&bytes(&vectors["secnonce"])), &secret)
+                .unwrap();
+            assert_eq!(
+                partial.to_bytes().to_vec(),
+                bytes(&case["expected"]),
+                "{}",
+                case["comment"]
+            );
+            let signer = case["signer_index"].as_u64().unwrap() as usize;
+            assert!(session
+                .verify_partial(&partial, &nonces[signer], public_keys[signer])
+                .is_ok());
+        }
+
+        for case in vectors["error_test_cases"].as_array().unwrap() {
+            for tweak in select(tweaks, &case["tweak_indices"]) {
+                assert_eq!(
+                    decode_scalar::<Scalar>(&tweak),
+                    Err(ZkError::NonCanonicalScalar)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        for n in [1, 2, 3, 5] {
+            let signers = signers(n);
+            let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+            let (session, nonces, partials) = run(&signers, &key_agg, b"message");
+            for ((partial, nonce), public_key) in
+                partials.iter().zip(&nonces).zip(&signers.public_keys)
+            {
+                assert!(session.verify_partial(partial, nonce, *public_key).is_ok());
+            }
+            let signature = session.aggregate(&partials).unwrap();
+            let x_only = key_agg.x_only_public_key();
+            assert!(signature.verify(x_only, b"message").is_ok());
+            assert_eq!(
+                bip340::Signature::batch_verify(&[(x_only, b"message", signature)]),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_with_repeated_key() {
+        // One signer holding two of the aggregated keys signs once per occurrence.
+        let signers = signers(2);
+        let repeated = Signers {
+            secrets: vec![
+                signers.secrets[0].clone(),
+                signers.secrets[1].clone(),
+                signers.secrets[0].clone(),
+            ],
+            public_keys: vec![
+                signers.public_keys[0],
+                signers.public_keys[1],
+                signers.public_keys[0],
+            ],
+        };
+        let key_agg = KeyAggContext::new(&repeated.public_keys).unwrap();
+        let (session, _, partials) = run(&repeated, &key_agg, b"message");
+        assert!(session.aggregate(&partials).is_ok());
+    }
+
+    #[test]
+    fn test_sign_with_tweaks() {
+        let signers = signers(3);
+        let tweaks = [
+            (Scalar::from(7u64), true),
+            (Scalar::from(11u64), false),
+            (-Scalar::from(13u64), true),
+        ];
+        for count in 1..=tweaks.len() {
+            let mut key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+            let mut expected = key_agg.aggregate_public_key();
+            for &(tweak, x_only) in &tweaks[..count] {
+                key_agg = key_agg.apply_tweak(tweak, x_only).unwrap();
+                if x_only {
+                    expected = XOnlyPublicKey::new(expected).unwrap().point();
+                }
+                expected += ProjectivePoint::GENERATOR * tweak;
+            }
+            assert_eq!(key_agg.aggregate_public_key(), expected);
+
+            let (session, nonces, partials) = run(&signers, &key_agg, b"message");
+            for ((partial, nonce), public_key) in
+                partials.iter().zip(&nonces).zip(&signers.public_keys)
+            {
+                assert!(session.verify_partial(partial, nonce, *public_key).is_ok());
+            }
+            let signature = session.aggregate(&partials).unwrap();
+            assert!(signature
+                .verify(key_agg.x_only_public_key(), b"message")
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_tweak_to_identity() {
+        // The aggregate of the single key G is a*G, which the plain tweak -a cancels.
+        let key_agg = KeyAggContext::new(&[ProjectivePoint::GENERATOR]).unwrap();
+        let a = key_agg
+            .key_agg_coefficient(ProjectivePoint::GENERATOR)
+            .unwrap();
+        assert_eq!(key_agg.apply_tweak(-a, false), Err(ZkError::IdentityPoint));
+    }
+
+    #[test]
+    fn test_invalid_partial_detected() {
+        let signers = signers(3);
+        let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+        let (session, nonces, mut partials) = run(&signers, &key_agg, b"message");
+        partials[1] = PartialSignature::new(partials[1].s() + Scalar::ONE);
+        assert!(session
+            .verify_partial(&partials[0], &nonces[0], signers.public_keys[0])
+            .is_ok());
+        assert_eq!(
+            session.verify_partial(&partials[1], &nonces[1], signers.public_keys[1]),
+            Err(ZkError::ChallengeMismatch)
+        );
+        // A valid partial signature checked against another signer's nonce or key.
+        assert_eq!(
+            session.verify_partial(&partials[0], &nonces[2], signers.public_keys[0]),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            session.verify_partial(&partials[0], &nonces[0], signers.public_keys[2]),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            session.aggregate(&partials),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_session_bound_to_message() {
+        let signers = signers(2);
+        let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+        let (secret_nonces, public_nonces): (Vec<_>, Vec<_>) = signers
+            .secrets
+            .iter()
+            .map(|secret| nonce_gen(secret, &key_agg, Some(b"message")).unwrap())
+            .unzip();
+        let aggregate_nonce = aggregate_nonces(&public_nonces).unwrap();
+        let session = Session::new(&key_agg, &aggregate_nonce, b"message");
+        let other = Session::new(&key_agg, &aggregate_nonce, b"other");
+        assert_ne!(session.b, other.b);
+
+        // Signers that disagree on the message produce no valid signature.
+        let mut secret_nonces = secret_nonces.into_iter();
+        let partials = [
+            session
+                .sign(secret_nonces.next().unwrap(), &signers.secrets[0])
+                .unwrap(),
+            other
+                .sign(secret_nonces.next().unwrap(), &signers.secrets[1])
+                .unwrap(),
+        ];
+        assert_eq!(
+            session.aggregate(&partials),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_foreign_key_or_nonce() {
+        let signers = signers(2);
+        let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+        let outsider = SecretScalar::random(&mut rand::thread_rng());
+        let (nonce, public_nonce) = nonce_gen(&outsider, &key_agg, None).unwrap();
+        let session = Session::new(
+            &key_agg,
+            &aggregate_nonces(&[public_nonce]).unwrap(),
+            b"message",
+        );
+        assert!(matches!(
+            session.sign(nonce, &outsider),
+            Err(ZkError::InvalidInput(_))
+        ));
+
+        let (nonce, _) = nonce_gen(&signers.secrets[0], &key_agg, None).unwrap();
+        assert!(matches!(
+            session.sign(nonce, &signers.secrets[1]),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            session.verify_partial(
+                &PartialSignature::new(Scalar::ONE),
+                &public_nonce,
+                ProjectivePoint::GENERATOR * outsider.expose_secret()
+            ),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(matches!(
+            KeyAggContext::new(&[]),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert_eq!(
+            KeyAggContext::new(&[ProjectivePoint::GENERATOR, ProjectivePoint::IDENTITY]),
+            Err(ZkError::IdentityPoint)
+        );
+        assert!(matches!(
+            aggregate_nonces(&[]),
+            Err(ZkError::InvalidInput(_))
+        ));
+        let nonce = PublicNonce::new(ProjectivePoint::GENERATOR, ProjectivePoint::IDENTITY);
+        assert_eq!(aggregate_nonces(&[nonce]), Err(ZkError::IdentityPoint));
+        let key_agg = KeyAggContext::new(&[ProjectivePoint::GENERATOR]).unwrap();
+        assert!(matches!(
+            nonce_gen(&SecretScalar::new(Scalar::ZERO), &key_agg, None),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_sort() {
+        let signers = signers(4);
+        let sorted = key_sort(&signers.public_keys);
+        assert!(sorted
+            .windows(2)
+            .all(|pair| cbytes(&pair[0]) <= cbytes(&pair[1])));
+        let mut reversed = signers.public_keys.clone();
+        reversed.reverse();
+        assert_eq!(key_sort(&reversed), sorted);
+    }
+
+    #[test]
+    fn test_second_key_coefficient_is_one() {
+        let signers = signers(3);
+        let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+        assert_eq!(
+            key_agg.key_agg_coefficient(signers.public_keys[1]),
+            Some(Scalar::ONE)
+        );
+        assert_ne!(
+            key_agg.key_agg_coefficient(signers.public_keys[0]),
+            Some(Scalar::ONE)
+        );
+        assert_eq!(
+            key_agg.key_agg_coefficient(ProjectivePoint::GENERATOR),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nonce_gen_inputs() {
+        let secret = SecretScalar::new(Scalar::from(3u64));
+        let public_key = ProjectivePoint::GENERATOR * secret.expose_secret();
+        let nonce = |random: &[u8; 32], msg: Option<&[u8]>, extra_in: &[u8]| {
+            nonce_gen_internal(random, Some(&secret), public_key, &[], msg, extra_in)
+                .unwrap()
+                .1
+        };
+        let base = nonce(&[0; 32], None, &[]);
+        assert_eq!(nonce(&[0; 32], None, &[]), base);
+        assert_ne!(nonce(&[1; 32], None, &[]), base);
+        // An empty message differs from no message.
+        assert_ne!(nonce(&[0; 32], Some(&[]), &[]), base);
+        assert_ne!(nonce(&[0; 32], None, &[0]), base);
+        assert_ne!(base.r1(), base.r2());
+    }
+
+    #[test]
+    fn test_encoding() {
+        let signers = signers(2);
+        let key_agg = KeyAggContext::new(&signers.public_keys).unwrap();
+        let (session, nonces, partials) = run(&signers, &key_agg, b"message");
+        assert_eq!(
+            PublicNonce::from_bytes(&nonces[0].to_bytes()),
+            Ok(nonces[0])
+        );
+        assert_eq!(
+            PartialSignature::from_bytes(&partials[0].to_bytes()),
+            Ok(partials[0])
+        );
+        let aggregate_nonce = aggregate_nonces(&nonces).unwrap();
+        assert_eq!(
+            AggregateNonce::from_bytes(&aggregate_nonce.to_bytes()),
+            Ok(aggregate_nonce)
+        );
+        assert!(session.aggregate(&partials).is_ok());
+
+        // Nonces of opposite points sum to the point at infinity, which the aggregate nonce
+        // encodes as zeros and the session replaces by `G`.
+        let cancelling = [
+            nonces[0],
+            PublicNonce::new(-nonces[0].r1(), -nonces[0].r2()),
+        ];
+        let infinite = aggregate_nonces(&cancelling).unwrap();
+        assert_eq!(infinite.to_bytes(), [0; 66]);
+        assert_eq!(AggregateNonce::from_bytes(&[0; 66]), Ok(infinite));
+        let session = Session::new(&key_agg, &infinite, b"message");
+        assert_eq!(session.r, ProjectivePoint::GENERATOR);
+
+        assert_eq!(
+            PublicNonce::from_bytes(&[0; 66]),
+            Err(ZkError::MalformedPoint)
+        );
+        assert!(matches!(
+            PublicNonce::from_bytes(&[0; 65]),
+            Err(ZkError::Encoding(_))
+        ));
+        assert!(matches!(
+            PartialSignature::from_bytes(&[0xff; 32]),
+            Err(ZkError::NonCanonicalScalar)
+        ));
+    }
+}
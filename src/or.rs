@@ -0,0 +1,286 @@
+use k256::{
+    elliptic_curve::{group::Group, rand_core::CryptoRngCore},
+    ProjectivePoint,
+};
+use sha2::Sha256;
+
+use crate::{
+    hash::ZkDigest,
+    sigma::{Or, Schnorr, SigmaProof},
+    transcript::{self, Transcript},
+    SecretScalar, ZkError, ZkGroup,
+};
+
+/// Non-interactive proof that the prover knows the discrete logarithm of at least one of the
+/// points `y_1..y_n`, without revealing which.
+///
+/// This is the Fiat-Shamir transformation of the [`Or`] composition of [`Schnorr`] statements,
+/// e.g. to authenticate as one of a set of registered party keys anonymously. Each branch `i`
+/// holds a commitment `t_i`, a challenge `c_i` and a response `s_i`; the challenges sum to the
+/// challenge derived from the transcript, so the prover can choose all but one of them and
+/// simulate those branches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrDLogProof<P: Group = ProjectivePoint> {
+    branches: Vec<(P, P::Scalar, P::Scalar)>,
+}
+
+impl<P: ZkGroup> OrDLogProof<P> {
+    /// Creates a proof from the commitment `t_i`, challenge `c_i` and response `s_i` of every
+    /// branch.
+    pub fn new(branches: Vec<(P, P::Scalar, P::Scalar)>) -> Self {
+        Self { branches }
+    }
+
+    /// Returns the `(t_i, c_i, s_i)` of every branch, in the order of the points.
+    pub fn branches(&self) -> &[(P, P::Scalar, P::Scalar)] {
+        &self.branches
+    }
+
+    /// Generates a proof that the prover knows the discrete logarithm of one of `ys`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `index` - The position in `ys` of the point `x*G`.
+    /// * `x` - The secret number.
+    /// * `ys` - The points, one of which is `x*G`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `index` is out of range for `ys`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{generate_random_number, OrDLogProof, SecretScalar};
+    /// # use k256::ProjectivePoint;
+    /// let x = SecretScalar::random(&mut rand::thread_rng());
+    /// let ys = [
+    ///     ProjectivePoint::GENERATOR * generate_random_number(),
+    ///     ProjectivePoint::GENERATOR * x.expose_secret(),
+    ///     ProjectivePoint::GENERATOR * generate_random_number(),
+    /// ];
+    /// let proof = OrDLogProof::prove("sid", 1, 1, &x, &ys).unwrap();
+    /// assert!(proof.verify("sid", 1, &ys).is_ok());
+    /// ```
+    pub fn prove(
+        sid: &str,
+        pid: u32,
+        index: usize,
+        x: &SecretScalar<P::Scalar>,
+        ys: &[P],
+    ) -> Result<Self, ZkError> {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, index, x, ys)
+    }
+
+    /// Generates a proof like [`OrDLogProof::prove`] with the nonce and the simulated branches
+    /// drawn from `rng`.
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        index: usize,
+        x: &SecretScalar<P::Scalar>,
+        ys: &[P],
+    ) -> Result<Self, ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, index, x, ys)
+    }
+
+    /// Generates a proof like [`OrDLogProof::prove`] with the challenge derived from
+    /// `transcript`.
+    ///
+    /// The verifier must pass a transcript in the same state to
+    /// [`OrDLogProof::verify_with_transcript`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`OrDLogProof::prove`].
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        index: usize,
+        x: &SecretScalar<P::Scalar>,
+        ys: &[P],
+    ) -> Result<Self, ZkError> {
+        let witness = (index, x.clone());
+        let proof = SigmaProof::prove(rng, transcript, &statement(ys), &witness)?;
+        let branches = proof
+            .commitment()
+            .iter()
+            .zip(proof.response())
+            .map(|(t, (c, s))| (*t, *c, *s))
+            .collect();
+        Ok(Self::new(branches))
+    }
+
+    /// Verifies that the branch challenges sum to the challenge and every branch `i` satisfies
+    /// `s_i*G == t_i + c_i*y_i`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `ys` - The points the prover claims to know one discrete logarithm of.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if `ys` is empty or the proof has another number of
+    /// branches, [`ZkError::IdentityPoint`] if any `y_i` or `t_i` is the point at infinity and
+    /// [`ZkError::ChallengeMismatch`] if the proof does not hold.
+    pub fn verify(&self, sid: &str, pid: u32, ys: &[P]) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, ys)
+    }
+
+    /// Verifies a proof generated by [`OrDLogProof::prove_with_transcript`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`OrDLogProof::verify`].
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        ys: &[P],
+    ) -> Result<(), ZkError> {
+        let commitment = self.branches.iter().map(|(t, _, _)| *t).collect();
+        let response = self.branches.iter().map(|(_, c, s)| (*c, *s)).collect();
+        SigmaProof::<Or<Schnorr<P>>>::new(commitment, response).verify(transcript, &statement(ys))
+    }
+}
+
+fn statement<P: ZkGroup>(ys: &[P]) -> Or<Schnorr<P>> {
+    Or(ys
+        .iter()
+        .map(|y| Schnorr::new(P::generator(), *y))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_random_number;
+    use k256::Scalar;
+
+    const G: ProjectivePoint = ProjectivePoint::GENERATOR;
+
+    fn ring(n: usize, index: usize) -> (SecretScalar, Vec<ProjectivePoint>) {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let ys = (0..n)
+            .map(|i| {
+                if i == index {
+                    G * x.expose_secret()
+                } else {
+                    G * generate_random_number()
+                }
+            })
+            .collect();
+        (x, ys)
+    }
+
+    #[test]
+    fn test_verify_every_index() {
+        for n in 1..=4 {
+            for index in 0..n {
+                let (x, ys) = ring(n, index);
+                let proof = OrDLogProof::prove("sid", 1, index, &x, &ys).unwrap();
+                assert_eq!(proof.branches().len(), n);
+                assert!(proof.verify("sid", 1, &ys).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_sid_or_pid() {
+        let (x, ys) = ring(3, 2);
+        let proof = OrDLogProof::prove("sid", 1, 2, &x, &ys).unwrap();
+        assert_eq!(proof.verify("abc", 1, &ys), Err(ZkError::ChallengeMismatch));
+        assert_eq!(proof.verify("sid", 2, &ys), Err(ZkError::ChallengeMismatch));
+    }
+
+    #[test]
+    fn test_verify_failed_without_witness() {
+        // A witness for none of the points does not yield a valid proof.
+        let (_, ys) = ring(3, 0);
+        let other = SecretScalar::random(&mut rand::thread_rng());
+        let proof = OrDLogProof::prove("sid", 1, 0, &other, &ys).unwrap();
+        assert_eq!(proof.verify("sid", 1, &ys), Err(ZkError::ChallengeMismatch));
+    }
+
+    #[test]
+    fn test_verify_failed_other_ring() {
+        let (x, ys) = ring(3, 1);
+        let proof = OrDLogProof::prove("sid", 1, 1, &x, &ys).unwrap();
+        let mut reordered = ys.clone();
+        reordered.swap(0, 2);
+        assert_eq!(
+            proof.verify("sid", 1, &reordered),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert!(matches!(
+            proof.verify("sid", 1, &ys[..2]),
+            Err(ZkError::Encoding(_))
+        ));
+        assert!(matches!(
+            OrDLogProof::<ProjectivePoint>::new(vec![]).verify("sid", 1, &[]),
+            Err(ZkError::Encoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_failed_tampered_challenges() {
+        // Shifting challenge weight between branches breaks their verification equations.
+        let (x, ys) = ring(2, 0);
+        let proof = OrDLogProof::prove("sid", 1, 0, &x, &ys).unwrap();
+        let mut branches = proof.branches().to_vec();
+        branches[0].1 += Scalar::ONE;
+        branches[1].1 -= Scalar::ONE;
+        assert_eq!(
+            OrDLogProof::new(branches).verify("sid", 1, &ys),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_identity() {
+        let (x, mut ys) = ring(2, 0);
+        ys[1] = ProjectivePoint::IDENTITY;
+        let proof = OrDLogProof::prove("sid", 1, 0, &x, &ys).unwrap();
+        assert_eq!(proof.verify("sid", 1, &ys), Err(ZkError::IdentityPoint));
+    }
+
+    #[test]
+    fn test_prove_index_out_of_range() {
+        let (x, ys) = ring(2, 0);
+        assert!(matches!(
+            OrDLogProof::prove("sid", 1, 2, &x, &ys),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            OrDLogProof::<ProjectivePoint>::prove("sid", 1, 0, &x, &[]),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_transcript_digest() {
+        let (x, ys) = ring(3, 1);
+        let transcript = Transcript::<sha3::Sha3_256>::with_digest(b"test");
+        let proof = OrDLogProof::prove_with_transcript(
+            &mut rand::thread_rng(),
+            &mut transcript.clone(),
+            1,
+            &x,
+            &ys,
+        )
+        .unwrap();
+        assert!(proof
+            .verify_with_transcript(&mut transcript.clone(), &ys)
+            .is_ok());
+        assert_eq!(
+            proof.verify_with_transcript(&mut Transcript::new(b"test"), &ys),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+}
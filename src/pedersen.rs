@@ -0,0 +1,351 @@
+use std::{ops::Add, sync::OnceLock};
+
+use k256::{
+    elliptic_curve::{
+        hash2curve::{ExpandMsgXmd, GroupDigest},
+        rand_core::CryptoRngCore,
+    },
+    ProjectivePoint, Scalar, Secp256k1,
+};
+use sha2::Sha256;
+
+use crate::{
+    hash::ZkDigest,
+    sigma::{Okamoto, SigmaProof},
+    transcript::{self, Transcript},
+    SecretScalar, ZkError,
+};
+
+/// The domain separation tag of the hash-to-curve suite deriving [`PedersenCommitment::h`] and
+/// the other generators of the crate.
+const GENERATOR_DST: &[u8] = b"zk_proof-v1-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+/// The message hashed to [`PedersenCommitment::h`].
+const H_MSG: &[u8] = b"Pedersen commitment generator H";
+
+/// Pedersen commitment `C = m*G + r*H` to a value `m` with the blinding factor `r` over
+/// secp256k1.
+///
+/// The commitment hides `m` perfectly and binds the committer to it as long as nobody knows the
+/// discrete logarithm of `H` relative to `G`, which is why `H` is hashed to the curve rather than
+/// chosen. Commitments are additively homomorphic: the sum of commitments to `m1` and `m2` is a
+/// commitment to `m1 + m2` with the sum of the blinding factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PedersenCommitment(ProjectivePoint);
+
+impl PedersenCommitment {
+    /// Wraps a commitment point received from another party.
+    pub fn new(point: ProjectivePoint) -> Self {
+        Self(point)
+    }
+
+    /// Returns the commitment point `C`.
+    pub fn point(&self) -> &ProjectivePoint {
+        &self.0
+    }
+
+    /// Returns the second generator `H`, a nothing-up-my-sleeve point with unknown discrete
+    /// logarithm.
+    ///
+    /// `H` is the RFC 9380 `secp256k1_XMD:SHA-256_SSWU_RO_` hash of a fixed message under the
+    /// tag `zk_proof-v1-secp256k1_XMD:SHA-256_SSWU_RO_`, so anyone can recompute it.
+    pub fn h() -> ProjectivePoint {
+        static H: OnceLock<ProjectivePoint> = OnceLock::new();
+        *H.get_or_init(|| hash_to_generator(&[H_MSG]))
+    }
+
+    /// Commits to `m` with the blinding factor `r`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{PedersenCommitment, SecretScalar};
+    /// # use k256::Scalar;
+    /// let m = SecretScalar::new(Scalar::from(42u64));
+    /// let r = SecretScalar::random(&mut rand::thread_rng());
+    /// let commitment = PedersenCommitment::commit(&m, &r);
+    /// assert!(commitment.opens_to(&Scalar::from(42u64), r.expose_secret()));
+    /// ```
+    pub fn commit(m: &SecretScalar, r: &SecretScalar) -> Self {
+        Self(ProjectivePoint::GENERATOR * m.expose_secret() + Self::h() * r.expose_secret())
+    }
+
+    /// Commits to `m` with a blinding factor drawn from `rng`, returning both.
+    pub fn commit_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        m: &SecretScalar,
+    ) -> (Self, SecretScalar) {
+        let r = SecretScalar::random(rng);
+        (Self::commit(m, &r), r)
+    }
+
+    /// Checks a revealed opening, i.e. that `C == m*G + r*H`.
+    pub fn opens_to(&self, m: &Scalar, r: &Scalar) -> bool {
+        self.0 == ProjectivePoint::GENERATOR * m + Self::h() * r
+    }
+}
+
+impl Add for PedersenCommitment {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+/// Non-interactive Okamoto proof of knowledge of the opening `(m, r)` of a
+/// [`PedersenCommitment`], without revealing it.
+///
+/// This is the Fiat-Shamir transformation of the [`Okamoto`] sigma protocol with the challenge
+/// bound to the session id and prover id like [`DLogProof`](crate::DLogProof).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpeningProof {
+    t: ProjectivePoint,
+    s_m: Scalar,
+    s_r: Scalar,
+}
+
+impl OpeningProof {
+    /// Creates a proof from its commitment `t = r_m*G + r_r*H` and responses `s_m` and `s_r`.
+    pub fn new(t: ProjectivePoint, s_m: Scalar, s_r: Scalar) -> Self {
+        Self { t, s_m, s_r }
+    }
+
+    /// Returns the commitment point `t`.
+    pub fn t(&self) -> &ProjectivePoint {
+        &self.t
+    }
+
+    /// Returns the response `s_m` for the committed value.
+    pub fn s_m(&self) -> &Scalar {
+        &self.s_m
+    }
+
+    /// Returns the response `s_r` for the blinding factor.
+    pub fn s_r(&self) -> &Scalar {
+        &self.s_r
+    }
+
+    /// Generates a proof that the prover knows `m` and `r` with `commitment = m*G + r*H`.
+    ///
+    /// The prover generates random numbers `r_m` and `r_r`, computes `t = r_m*G + r_r*H` and
+    /// `c = H(sid, pid, G, H, C, t)` over a SHA-256 transcript, and then computes
+    /// `s_m = r_m + c*m` and `s_r = r_r + c*r`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `m` - The committed value.
+    /// * `r` - The blinding factor.
+    /// * `commitment` - The commitment `m*G + r*H`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{OpeningProof, PedersenCommitment, SecretScalar};
+    /// let m = SecretScalar::random(&mut rand::thread_rng());
+    /// let (commitment, r) = PedersenCommitment::commit_with_rng(&mut rand::thread_rng(), &m);
+    /// let proof = OpeningProof::prove("sid", 1, &m, &r, &commitment);
+    /// assert!(proof.verify("sid", 1, &commitment).is_ok());
+    /// ```
+    pub fn prove(
+        sid: &str,
+        pid: u32,
+        m: &SecretScalar,
+        r: &SecretScalar,
+        commitment: &PedersenCommitment,
+    ) -> Self {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, m, r, commitment)
+    }
+
+    /// Generates a proof like [`OpeningProof::prove`] with the nonces drawn from `rng`.
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        m: &SecretScalar,
+        r: &SecretScalar,
+        commitment: &PedersenCommitment,
+    ) -> Self {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, m, r, commitment)
+    }
+
+    /// Generates a proof like [`OpeningProof::prove`] with the challenge derived from
+    /// `transcript`.
+    ///
+    /// The verifier must pass a transcript in the same state to
+    /// [`OpeningProof::verify_with_transcript`].
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        m: &SecretScalar,
+        r: &SecretScalar,
+        commitment: &PedersenCommitment,
+    ) -> Self {
+        let witness = (m.clone(), r.clone());
+        let proof = SigmaProof::prove(rng, transcript, &statement(commitment), &witness)
+            .expect("an Okamoto commitment does not fail");
+        let (s_m, s_r) = *proof.response();
+        Self::new(*proof.commitment(), s_m, s_r)
+    }
+
+    /// Verifies that `s_m*G + s_r*H == t + c*C`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `commitment` - The commitment the prover claims to be able to open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] if the commitment or `t` is the point at infinity and
+    /// [`ZkError::ChallengeMismatch`] if the proof does not hold.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: u32,
+        commitment: &PedersenCommitment,
+    ) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, commitment)
+    }
+
+    /// Verifies a proof generated by [`OpeningProof::prove_with_transcript`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`OpeningProof::verify`].
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        commitment: &PedersenCommitment,
+    ) -> Result<(), ZkError> {
+        SigmaProof::<Okamoto<ProjectivePoint>>::new(self.t, (self.s_m, self.s_r))
+            .verify(transcript, &statement(commitment))
+    }
+}
+
+/// Hashes the concatenation of `msg` to a generator with unknown discrete logarithm.
+pub(crate) fn hash_to_generator(msg: &[&[u8]]) -> ProjectivePoint {
+    Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(msg, &[GENERATOR_DST])
+        .expect("the tag is valid for expand_message_xmd")
+}
+
+fn statement(commitment: &PedersenCommitment) -> Okamoto<ProjectivePoint> {
+    Okamoto::new(
+        ProjectivePoint::GENERATOR,
+        PedersenCommitment::h(),
+        commitment.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::elliptic_curve::group::GroupEncoding;
+
+    fn opening() -> (SecretScalar, SecretScalar, PedersenCommitment) {
+        let m = SecretScalar::random(&mut rand::thread_rng());
+        let (commitment, r) = PedersenCommitment::commit_with_rng(&mut rand::thread_rng(), &m);
+        (m, r, commitment)
+    }
+
+    #[test]
+    fn test_h() {
+        assert_eq!(
+            hex::encode(PedersenCommitment::h().to_bytes()),
+            "03ac493e199ad34407bb35eb2ebcc41a7c630bf271ea89b8bb1f98c5f7e5f2dd2c"
+        );
+        assert_ne!(PedersenCommitment::h(), ProjectivePoint::GENERATOR);
+    }
+
+    #[test]
+    fn test_opens_to() {
+        let (m, r, commitment) = opening();
+        assert!(commitment.opens_to(m.expose_secret(), r.expose_secret()));
+        assert!(!commitment.opens_to(r.expose_secret(), m.expose_secret()));
+        assert!(!commitment.opens_to(&(m.expose_secret() + Scalar::ONE), r.expose_secret()));
+    }
+
+    #[test]
+    fn test_homomorphic() {
+        let (m1, r1, c1) = opening();
+        let (m2, r2, c2) = opening();
+        let m = m1.expose_secret() + m2.expose_secret();
+        let r = r1.expose_secret() + r2.expose_secret();
+        assert!((c1 + c2).opens_to(&m, &r));
+    }
+
+    #[test]
+    fn test_verify() {
+        let (m, r, commitment) = opening();
+        let proof = OpeningProof::prove("sid", 1, &m, &r, &commitment);
+        assert!(proof.verify("sid", 1, &commitment).is_ok());
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_sid_or_pid() {
+        let (m, r, commitment) = opening();
+        let proof = OpeningProof::prove("sid", 1, &m, &r, &commitment);
+        assert_eq!(
+            proof.verify("abc", 1, &commitment),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            proof.verify("sid", 2, &commitment),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_opening() {
+        let (m, _, commitment) = opening();
+        let other = SecretScalar::random(&mut rand::thread_rng());
+        let proof = OpeningProof::prove("sid", 1, &m, &other, &commitment);
+        assert_eq!(
+            proof.verify("sid", 1, &commitment),
+            Err(ZkError::ChallengeMismatch)
+        );
+
+        let (m, r, commitment) = opening();
+        let proof = OpeningProof::prove("sid", 1, &m, &r, &commitment);
+        let (_, _, other) = opening();
+        assert_eq!(
+            proof.verify("sid", 1, &other),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_identity() {
+        let identity = PedersenCommitment::new(ProjectivePoint::IDENTITY);
+        let trivial = OpeningProof::new(ProjectivePoint::IDENTITY, Scalar::ZERO, Scalar::ZERO);
+        assert_eq!(
+            trivial.verify("sid", 1, &identity),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_transcript_digest() {
+        let (m, r, commitment) = opening();
+        let transcript = Transcript::<sha3::Sha3_256>::with_digest(b"test");
+        let proof = OpeningProof::prove_with_transcript(
+            &mut rand::thread_rng(),
+            &mut transcript.clone(),
+            &m,
+            &r,
+            &commitment,
+        );
+        assert!(proof
+            .verify_with_transcript(&mut transcript.clone(), &commitment)
+            .is_ok());
+        assert_eq!(
+            proof.verify_with_transcript(&mut Transcript::new(b"test"), &commitment),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+}
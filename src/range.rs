@@ -0,0 +1,975 @@
+use std::sync::{Mutex, PoisonError};
+
+use k256::{
+    elliptic_curve::{
+        group::{Group, GroupEncoding},
+        rand_core::CryptoRngCore,
+        zeroize::Zeroize,
+        Field, PrimeField,
+    },
+    ProjectivePoint, Scalar,
+};
+use sha2::Sha256;
+
+use crate::{
+    encoding::decode_scalar,
+    group::ensure_non_identity,
+    hash::ZkDigest,
+    msm::msm,
+    pedersen::hash_to_generator,
+    transcript::{self, Transcript},
+    PedersenCommitment, SecretScalar, ZkError, ZkGroup,
+};
+
+/// The name binding [`RangeProof`] challenges to this kind of proof.
+const PROOF: &[u8] = b"RangeProof";
+
+/// The bit sizes a range can be proven for.
+const BIT_SIZES: [usize; 4] = [8, 16, 32, 64];
+
+/// The length of a compressed point.
+const POINT_LEN: usize = 33;
+
+/// The length of a scalar.
+const SCALAR_LEN: usize = 32;
+
+/// The length of the fixed part of the encoding: `A`, `S`, `T1`, `T2`, `t_hat`, `tau_x`, `mu`,
+/// `a` and `b`.
+const FIXED_LEN: usize = 4 * POINT_LEN + 5 * SCALAR_LEN;
+
+/// Bulletproofs range proof that each of `m` [`PedersenCommitment`]s `V_j = v_j*G + gamma_j*H`
+/// commits to a value in `[0, 2^n)`, without revealing the values.
+///
+/// The proof is the aggregated range proof of Bünz et al. with its inner-product argument, so it
+/// holds `2*log2(n*m)` points besides a constant number of elements: 688 bytes for one 64-bit
+/// value and 754 bytes for two. `n` is 8, 16, 32 or 64 and `m` a power of two. The commitments
+/// use the generators of [`PedersenCommitment`]; the vector generators `G_i` and `H_i` are hashed
+/// to the curve like [`PedersenCommitment::h`], so nobody knows discrete logarithms between any
+/// of them.
+///
+/// The challenges are derived from a [`Transcript`] bound to the session id and prover id like
+/// [`DLogProof`](crate::DLogProof), and verification is a single multi-scalar multiplication,
+/// which [`RangeProof::batch_verify`] shares between many proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeProof {
+    a: ProjectivePoint,
+    s: ProjectivePoint,
+    t1: ProjectivePoint,
+    t2: ProjectivePoint,
+    t_hat: Scalar,
+    tau_x: Scalar,
+    mu: Scalar,
+    l: Vec<ProjectivePoint>,
+    r: Vec<ProjectivePoint>,
+    ipa_a: Scalar,
+    ipa_b: Scalar,
+}
+
+/// The coefficients of a weighted verification equation, accumulated over one or more proofs.
+struct Equation {
+    g: Scalar,
+    h: Scalar,
+    gs: Vec<Scalar>,
+    hs: Vec<Scalar>,
+    scalars: Vec<Scalar>,
+    points: Vec<ProjectivePoint>,
+}
+
+impl Equation {
+    fn new(nm: usize) -> Self {
+        Self {
+            g: Scalar::ZERO,
+            h: Scalar::ZERO,
+            gs: vec![Scalar::ZERO; nm],
+            hs: vec![Scalar::ZERO; nm],
+            scalars: Vec::new(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Checks that the equation sums to the identity.
+    fn holds(mut self) -> bool {
+        let (gs, hs) = generators(self.gs.len());
+        self.scalars.extend([self.g, self.h]);
+        self.points
+            .extend([ProjectivePoint::GENERATOR, PedersenCommitment::h()]);
+        self.scalars.extend(self.gs);
+        self.points.extend(gs);
+        self.scalars.extend(self.hs);
+        self.points.extend(hs);
+        bool::from(msm(&self.scalars, &self.points).is_identity())
+    }
+}
+
+impl RangeProof {
+    /// Returns the number of values proven by a proof of this size, i.e. `n*m`.
+    fn capacity(&self) -> usize {
+        1 << self.l.len()
+    }
+
+    /// Generates a proof that every value lies in `[0, 2^bits)`, committing to `values[j]` with
+    /// the blinding factor `blindings[j]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `values` - The values, as many as a power of two.
+    /// * `blindings` - The blinding factor of each value.
+    /// * `bits` - The bit size `n` of the range, 8, 16, 32 or 64.
+    ///
+    /// # Returns
+    ///
+    /// The proof and the commitments to the values, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `bits` is not supported, the number of values is not
+    /// a power of two or differs from the number of blinding factors, or a value is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{RangeProof, SecretScalar};
+    /// let blindings = [
+    ///     SecretScalar::random(&mut rand::thread_rng()),
+    ///     SecretScalar::random(&mut rand::thread_rng()),
+    /// ];
+    /// let (proof, commitments) = RangeProof::prove("sid", 1, &[42, 7], &blindings, 8).unwrap();
+    /// assert!(proof.verify("sid", 1, &commitments, 8).is_ok());
+    /// assert!(RangeProof::prove("sid", 1, &[256, 7], &blindings, 8).is_err());
+    /// ```
+    pub fn prove(
+        sid: &str,
+        pid: u32,
+        values: &[u64],
+        blindings: &[SecretScalar],
+        bits: usize,
+    ) -> Result<(Self, Vec<PedersenCommitment>), ZkError> {
+        Self::prove_with_rng(&mut rand::thread_rng(), sid, pid, values, blindings, bits)
+    }
+
+    /// Generates a proof like [`RangeProof::prove`] with the blinding vectors and nonces drawn
+    /// from `rng`.
+    pub fn prove_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        sid: &str,
+        pid: u32,
+        values: &[u64],
+        blindings: &[SecretScalar],
+        bits: usize,
+    ) -> Result<(Self, Vec<PedersenCommitment>), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        Self::prove_with_transcript(rng, &mut transcript, values, blindings, bits)
+    }
+
+    /// Generates a proof like [`RangeProof::prove`] with the challenges derived from
+    /// `transcript`.
+    ///
+    /// The verifier must pass a transcript in the same state to
+    /// [`RangeProof::verify_with_transcript`].
+    pub fn prove_with_transcript<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        values: &[u64],
+        blindings: &[SecretScalar],
+        bits: usize,
+    ) -> Result<(Self, Vec<PedersenCommitment>), ZkError> {
+        let m = values.len();
+        check_parameters(bits, m)?;
+        if blindings.len() != m {
+            return Err(ZkError::InvalidInput(format!(
+                "expected {} blinding factors, got {}",
+                m,
+                blindings.len()
+            )));
+        }
+        if bits < 64 && values.iter().any(|value| value >> bits != 0) {
+            return Err(ZkError::InvalidInput(format!(
+                "value out of the range [0, 2^{})",
+                bits
+            )));
+        }
+
+        let nm = bits * m;
+        let (gs, hs) = generators(nm);
+        let h = PedersenCommitment::h();
+        let commitments: Vec<_> = values
+            .iter()
+            .zip(blindings)
+            .map(|(value, gamma)| {
+                PedersenCommitment::commit(&SecretScalar::new(Scalar::from(*value)), gamma)
+            })
+            .collect();
+        begin(transcript, bits, &commitments);
+
+        // a_L holds the bits of the values and a_R = a_L - 1, so a_L * a_R = 0.
+        let mut a_l: Vec<_> = values
+            .iter()
+            .flat_map(|value| (0..bits).map(move |i| Scalar::from((value >> i) & 1)))
+            .collect();
+        let mut a_r: Vec<_> = a_l.iter().map(|bit| bit - &Scalar::ONE).collect();
+        let alpha = SecretScalar::<Scalar>::random(rng);
+        let a = vector_commitment(&a_l, &a_r, alpha.expose_secret(), &gs, &hs, h);
+
+        let mut s_l: Vec<_> = (0..nm).map(|_| Scalar::random(&mut *rng)).collect();
+        let mut s_r: Vec<_> = (0..nm).map(|_| Scalar::random(&mut *rng)).collect();
+        let rho = SecretScalar::<Scalar>::random(rng);
+        let s = vector_commitment(&s_l, &s_r, rho.expose_secret(), &gs, &hs, h);
+
+        transcript.append_point(b"A", &a);
+        transcript.append_point(b"S", &s);
+        let y: Scalar = transcript.challenge_scalar(b"y");
+        let z: Scalar = transcript.challenge_scalar(b"z");
+
+        // l(X) = l0 + l1*X and r(X) = r0 + r1*X, with t(X) = <l(X), r(X)> = t0 + t1*X + t2*X^2.
+        let y_powers = powers(y, nm);
+        let z_two = z_and_two(z, bits, m);
+        let mut l0: Vec<_> = a_l.iter().map(|a| a - &z).collect();
+        let mut r0: Vec<_> = (0..nm)
+            .map(|i| y_powers[i] * (a_r[i] + z) + z * z * z_two[i])
+            .collect();
+        let mut r1: Vec<_> = (0..nm).map(|i| y_powers[i] * s_r[i]).collect();
+        let t1 = inner_product(&l0, &r1) + inner_product(&s_l, &r0);
+        let t2 = inner_product(&s_l, &r1);
+
+        let tau1 = SecretScalar::<Scalar>::random(&mut *rng);
+        let tau2 = SecretScalar::<Scalar>::random(rng);
+        let big_t1 = ProjectivePoint::GENERATOR * t1 + h * tau1.expose_secret();
+        let big_t2 = ProjectivePoint::GENERATOR * t2 + h * tau2.expose_secret();
+        transcript.append_point(b"T1", &big_t1);
+        transcript.append_point(b"T2", &big_t2);
+        let x: Scalar = transcript.challenge_scalar(b"x");
+
+        let l: Vec<_> = (0..nm).map(|i| l0[i] + s_l[i] * x).collect();
+        let r: Vec<_> = (0..nm).map(|i| r0[i] + r1[i] * x).collect();
+        let t_hat = inner_product(&l, &r);
+        let gammas = blindings
+            .iter()
+            .zip(powers(z, m))
+            .fold(Scalar::ZERO, |sum, (gamma, z_j)| {
+                sum + z * z * z_j * gamma.expose_secret()
+            });
+        let tau_x = tau2.expose_secret() * &(x * x) + tau1.expose_secret() * &x + gammas;
+        let mu = rho.expose_secret() * &x + alpha.expose_secret();
+        transcript.append_scalar(b"t_hat", &t_hat);
+        transcript.append_scalar(b"tau_x", &tau_x);
+        transcript.append_scalar(b"mu", &mu);
+        let w: Scalar = transcript.challenge_scalar(b"w");
+
+        // The inner-product argument runs over H'_i = y^-i * H_i.
+        let y_inv = Option::from(y.invert()).ok_or(ZkError::ChallengeMismatch)?;
+        let hs_prime = hs
+            .iter()
+            .zip(powers(y_inv, nm))
+            .map(|(h, y_inv_i)| *h * y_inv_i)
+            .collect();
+        let q = ProjectivePoint::GENERATOR * w;
+        let (l_vec, r_vec, ipa_a, ipa_b) = inner_product_proof(transcript, q, gs, hs_prime, l, r)?;
+
+        for secret in [
+            &mut a_l, &mut a_r, &mut s_l, &mut s_r, &mut l0, &mut r0, &mut r1,
+        ] {
+            secret.zeroize();
+        }
+        let proof = Self {
+            a,
+            s,
+            t1: big_t1,
+            t2: big_t2,
+            t_hat,
+            tau_x,
+            mu,
+            l: l_vec,
+            r: r_vec,
+            ipa_a,
+            ipa_b,
+        };
+        Ok((proof, commitments))
+    }
+
+    /// Verifies that each of `commitments` commits to a value in `[0, 2^bits)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the prover.
+    /// * `commitments` - The commitments returned by [`RangeProof::prove`].
+    /// * `bits` - The bit size `n` of the range.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if `bits` is not supported or the number of commitments
+    /// is not a power of two, [`ZkError::Encoding`] if the proof has the wrong size for them,
+    /// [`ZkError::IdentityPoint`] if a commitment or a point of the proof is the point at
+    /// infinity and [`ZkError::ChallengeMismatch`] if the proof does not hold.
+    pub fn verify(
+        &self,
+        sid: &str,
+        pid: u32,
+        commitments: &[PedersenCommitment],
+        bits: usize,
+    ) -> Result<(), ZkError> {
+        let mut transcript = transcript::session::<Sha256>(sid, pid);
+        self.verify_with_transcript(&mut transcript, commitments, bits)
+    }
+
+    /// Verifies a proof generated by [`RangeProof::prove_with_transcript`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`RangeProof::verify`].
+    pub fn verify_with_transcript<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        commitments: &[PedersenCommitment],
+        bits: usize,
+    ) -> Result<(), ZkError> {
+        let mut equation = Equation::new(bits * commitments.len());
+        self.add_equation(
+            &mut rand::thread_rng(),
+            transcript,
+            commitments,
+            bits,
+            Scalar::ONE,
+            &mut equation,
+        )?;
+        if !equation.holds() {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verifies many proofs for the same bit size at once with a single multi-scalar
+    /// multiplication.
+    ///
+    /// The verification equation of each proof is scaled by a random weight and all of them are
+    /// summed, which holds for an invalid proof only with negligible probability. The generators
+    /// shared by the proofs are multiplied once.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The `(sid, pid, commitments, proof)` tuples to verify.
+    /// * `bits` - The bit size `n` of the ranges.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if every proof is valid, otherwise `Err(i)` with the index of the first invalid
+    /// proof.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zk_proof::{RangeProof, SecretScalar};
+    /// let proofs: Vec<_> = (1..=3)
+    ///     .map(|pid| {
+    ///         let blinding = SecretScalar::random(&mut rand::thread_rng());
+    ///         RangeProof::prove("sid", pid, &[u64::from(pid)], &[blinding], 16).unwrap()
+    ///     })
+    ///     .collect();
+    /// let items: Vec<_> = proofs
+    ///     .iter()
+    ///     .zip(1..)
+    ///     .map(|((proof, commitments), pid)| ("sid", pid, commitments.as_slice(), proof))
+    ///     .collect();
+    /// assert_eq!(RangeProof::batch_verify(&items, 16), Ok(()));
+    /// ```
+    pub fn batch_verify(
+        items: &[(&str, u32, &[PedersenCommitment], &RangeProof)],
+        bits: usize,
+    ) -> Result<(), usize> {
+        Self::batch_verify_with_rng(&mut rand::thread_rng(), items, bits)
+    }
+
+    /// Verifies many proofs like [`RangeProof::batch_verify`] with the random weights drawn from
+    /// `rng`.
+    pub fn batch_verify_with_rng(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        items: &[(&str, u32, &[PedersenCommitment], &RangeProof)],
+        bits: usize,
+    ) -> Result<(), usize> {
+        let nm = items
+            .iter()
+            .map(|(_, _, commitments, _)| bits * commitments.len())
+            .max()
+            .unwrap_or(0);
+        let mut equation = Equation::new(nm);
+        let mut failed = false;
+        for (sid, pid, commitments, proof) in items {
+            let mut transcript = transcript::session::<Sha256>(sid, *pid);
+            let weight = Scalar::random(&mut *rng);
+            // An earlier proof may fail only the combined equation, so the first invalid proof
+            // is left to the scan below.
+            if proof
+                .add_equation(
+                    rng,
+                    &mut transcript,
+                    commitments,
+                    bits,
+                    weight,
+                    &mut equation,
+                )
+                .is_err()
+            {
+                failed = true;
+                break;
+            }
+        }
+
+        if !failed && equation.holds() {
+            return Ok(());
+        }
+        match items.iter().position(|(sid, pid, commitments, proof)| {
+            proof.verify(sid, *pid, commitments, bits).is_err()
+        }) {
+            Some(index) => Err(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the verification equation of the proof, scaled by `weight`, to `equation`.
+    ///
+    /// The equation combines the check of `t_hat` against `T1`, `T2` and the commitments, scaled
+    /// by a random `c`, with the check of the inner-product argument, and sums to the identity
+    /// for a valid proof.
+    fn add_equation<D: ZkDigest>(
+        &self,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        commitments: &[PedersenCommitment],
+        bits: usize,
+        weight: Scalar,
+        equation: &mut Equation,
+    ) -> Result<(), ZkError> {
+        let m = commitments.len();
+        check_parameters(bits, m)?;
+        let nm = bits * m;
+        if self.l.len() != self.r.len() || self.l.len() >= 32 || self.capacity() != nm {
+            return Err(ZkError::Encoding(format!(
+                "expected {} inner-product rounds for {} bits and {} commitments",
+                nm.trailing_zeros(),
+                bits,
+                m
+            )));
+        }
+        let points: Vec<_> = commitments
+            .iter()
+            .map(|commitment| *commitment.point())
+            .chain([self.a, self.s, self.t1, self.t2])
+            .chain(self.l.iter().chain(&self.r).copied())
+            .collect();
+        ensure_non_identity(&points)?;
+
+        begin(transcript, bits, commitments);
+        transcript.append_point(b"A", &self.a);
+        transcript.append_point(b"S", &self.s);
+        let y: Scalar = transcript.challenge_scalar(b"y");
+        let z: Scalar = transcript.challenge_scalar(b"z");
+        transcript.append_point(b"T1", &self.t1);
+        transcript.append_point(b"T2", &self.t2);
+        let x: Scalar = transcript.challenge_scalar(b"x");
+        transcript.append_scalar(b"t_hat", &self.t_hat);
+        transcript.append_scalar(b"tau_x", &self.tau_x);
+        transcript.append_scalar(b"mu", &self.mu);
+        let w: Scalar = transcript.challenge_scalar(b"w");
+        let mut u = Vec::with_capacity(self.l.len());
+        for (l, r) in self.l.iter().zip(&self.r) {
+            transcript.append_point(b"L", l);
+            transcript.append_point(b"R", r);
+            u.push(transcript.challenge_scalar::<Scalar>(b"u"));
+        }
+
+        let invert =
+            |scalar: Scalar| Option::from(scalar.invert()).ok_or(ZkError::ChallengeMismatch);
+        let y_inv = invert(y)?;
+        let u_inv = u
+            .iter()
+            .map(|u| invert(*u))
+            .collect::<Result<Vec<_>, _>>()?;
+        let s = folding_scalars(&u, &u_inv);
+        let c = Scalar::random(&mut *rng);
+
+        let z2 = z * z;
+        let y_powers = powers(y, nm);
+        let y_sum = y_powers.iter().fold(Scalar::ZERO, |sum, y_i| sum + y_i);
+        let two_sum = Scalar::from(u64::MAX >> (64 - bits));
+        let z_powers = powers(z, m);
+        let delta = (z - z2) * y_sum
+            - z_powers
+                .iter()
+                .fold(Scalar::ZERO, |sum, z_j| sum + z2 * z * z_j * two_sum);
+        let ab = self.ipa_a * self.ipa_b;
+
+        equation.g += weight * (w * (self.t_hat - ab) + c * (delta - self.t_hat));
+        equation.h += weight * (-self.mu - c * self.tau_x);
+        let z_two = z_and_two(z, bits, m);
+        let y_inv_powers = powers(y_inv, nm);
+        for i in 0..nm {
+            equation.gs[i] += weight * (-z - self.ipa_a * s[i]);
+            equation.hs[i] +=
+                weight * (z + y_inv_powers[i] * (z2 * z_two[i] - self.ipa_b * s[nm - 1 - i]));
+        }
+        equation
+            .scalars
+            .extend([weight, weight * x, weight * c * x, weight * c * x * x]);
+        equation.points.extend([self.a, self.s, self.t1, self.t2]);
+        for ((l, r), (u, u_inv)) in self.l.iter().zip(&self.r).zip(u.iter().zip(&u_inv)) {
+            equation
+                .scalars
+                .extend([weight * u.square(), weight * u_inv.square()]);
+            equation.points.extend([*l, *r]);
+        }
+        for (commitment, z_j) in commitments.iter().zip(z_powers) {
+            equation.scalars.push(weight * c * z2 * z_j);
+            equation.points.push(*commitment.point());
+        }
+        Ok(())
+    }
+
+    /// Encodes the proof as the compressed points `A`, `S`, `T1`, `T2`, the big-endian scalars
+    /// `t_hat`, `tau_x`, `mu`, `a`, `b` and then the compressed `L_k` and `R_k` of each round.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FIXED_LEN + 2 * POINT_LEN * self.l.len());
+        for point in [self.a, self.s, self.t1, self.t2] {
+            bytes.extend_from_slice(&point.to_bytes());
+        }
+        for scalar in [self.t_hat, self.tau_x, self.mu, self.ipa_a, self.ipa_b] {
+            bytes.extend_from_slice(&scalar.to_repr());
+        }
+        for (l, r) in self.l.iter().zip(&self.r) {
+            bytes.extend_from_slice(&l.to_bytes());
+            bytes.extend_from_slice(&r.to_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a proof encoded with [`RangeProof::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if the length is not that of a proof,
+    /// [`ZkError::MalformedPoint`] for invalid points and [`ZkError::NonCanonicalScalar`] for
+    /// scalars that are not smaller than the group order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZkError> {
+        let rounds = bytes.len().saturating_sub(FIXED_LEN) / (2 * POINT_LEN);
+        if bytes.len() != FIXED_LEN + 2 * POINT_LEN * rounds || rounds >= 32 {
+            return Err(ZkError::Encoding(format!(
+                "invalid range proof length {}",
+                bytes.len()
+            )));
+        }
+        let (fixed, rounds) = bytes.split_at(FIXED_LEN);
+        let (points, scalars) = fixed.split_at(4 * POINT_LEN);
+        let points = points
+            .chunks(POINT_LEN)
+            .map(ProjectivePoint::decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        let scalars = scalars
+            .chunks(SCALAR_LEN)
+            .map(decode_scalar)
+            .collect::<Result<Vec<Scalar>, _>>()?;
+        let rounds = rounds
+            .chunks(POINT_LEN)
+            .map(ProjectivePoint::decode)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            a: points[0],
+            s: points[1],
+            t1: points[2],
+            t2: points[3],
+            t_hat: scalars[0],
+            tau_x: scalars[1],
+            mu: scalars[2],
+            ipa_a: scalars[3],
+            ipa_b: scalars[4],
+            l: rounds.iter().step_by(2).copied().collect(),
+            r: rounds.iter().skip(1).step_by(2).copied().collect(),
+        })
+    }
+}
+
+/// Checks that `bits` is supported and `m` values can be aggregated.
+fn check_parameters(bits: usize, m: usize) -> Result<(), ZkError> {
+    if !BIT_SIZES.contains(&bits) {
+        return Err(ZkError::InvalidInput(format!(
+            "unsupported range bit size {}",
+            bits
+        )));
+    }
+    if !m.is_power_of_two() {
+        return Err(ZkError::InvalidInput(format!(
+            "the number of values must be a power of two, got {}",
+            m
+        )));
+    }
+    Ok(())
+}
+
+/// Appends the statement, i.e. the range and the commitments, to `transcript`.
+fn begin<D: ZkDigest>(
+    transcript: &mut Transcript<D>,
+    bits: usize,
+    commitments: &[PedersenCommitment],
+) {
+    transcript.append_message(b"proof", PROOF);
+    transcript.append_u32(b"n", bits as u32);
+    transcript.append_u32(b"m", commitments.len() as u32);
+    for commitment in commitments {
+        transcript.append_point(b"V", commitment.point());
+    }
+}
+
+/// Returns the first `n` vector generators `G_i` and `H_i`.
+fn generators(n: usize) -> (Vec<ProjectivePoint>, Vec<ProjectivePoint>) {
+    type Generators = (Vec<ProjectivePoint>, Vec<ProjectivePoint>);
+    static GENERATORS: Mutex<Generators> = Mutex::new((Vec::new(), Vec::new()));
+
+    let mut generators = GENERATORS.lock().unwrap_or_else(PoisonError::into_inner);
+    while generators.0.len() < n {
+        let i = (generators.0.len() as u32).to_be_bytes();
+        generators
+            .0
+            .push(hash_to_generator(&[b"Bulletproofs generator G", &i]));
+        generators
+            .1
+            .push(hash_to_generator(&[b"Bulletproofs generator H", &i]));
+    }
+    (generators.0[..n].to_vec(), generators.1[..n].to_vec())
+}
+
+/// Computes `<a_l, G> + <a_r, H> + blinding*h`.
+fn vector_commitment(
+    a_l: &[Scalar],
+    a_r: &[Scalar],
+    blinding: &Scalar,
+    gs: &[ProjectivePoint],
+    hs: &[ProjectivePoint],
+    h: ProjectivePoint,
+) -> ProjectivePoint {
+    let scalars: Vec<_> = a_l.iter().chain(a_r).chain([blinding]).copied().collect();
+    let points: Vec<_> = gs.iter().chain(hs).chain([&h]).copied().collect();
+    msm(&scalars, &points)
+}
+
+/// Returns `1, x, x^2, ..., x^(n-1)`.
+fn powers(x: Scalar, n: usize) -> Vec<Scalar> {
+    std::iter::successors(Some(Scalar::ONE), |power| Some(power * &x))
+        .take(n)
+        .collect()
+}
+
+/// Returns `z^j * 2^k` at index `j*n + k`, the weights of the bits of value `j`.
+fn z_and_two(z: Scalar, bits: usize, m: usize) -> Vec<Scalar> {
+    let twos = powers(Scalar::from(2u64), bits);
+    powers(z, m)
+        .into_iter()
+        .flat_map(|z_j| twos.iter().map(move |two| z_j * two))
+        .collect()
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter()
+        .zip(b)
+        .fold(Scalar::ZERO, |sum, (a, b)| sum + a * b)
+}
+
+/// Proves `P = <a, G> + <b, H> + <a, b>*q` in `log2(n)` rounds, halving the vectors with the
+/// challenge `u_k` of each round.
+fn inner_product_proof<D: ZkDigest>(
+    transcript: &mut Transcript<D>,
+    q: ProjectivePoint,
+    mut gs: Vec<ProjectivePoint>,
+    mut hs: Vec<ProjectivePoint>,
+    mut a: Vec<Scalar>,
+    mut b: Vec<Scalar>,
+) -> Result<(Vec<ProjectivePoint>, Vec<ProjectivePoint>, Scalar, Scalar), ZkError> {
+    let mut l_vec = Vec::new();
+    let mut r_vec = Vec::new();
+    let mut n = a.len();
+    while n > 1 {
+        n /= 2;
+        let c_l = inner_product(&a[..n], &b[n..]);
+        let c_r = inner_product(&a[n..], &b[..n]);
+        let l = msm(
+            &[&a[..n], &b[n..], &[c_l]].concat(),
+            &[&gs[n..], &hs[..n], &[q]].concat(),
+        );
+        let r = msm(
+            &[&a[n..], &b[..n], &[c_r]].concat(),
+            &[&gs[..n], &hs[n..], &[q]].concat(),
+        );
+        transcript.append_point(b"L", &l);
+        transcript.append_point(b"R", &r);
+        let u: Scalar = transcript.challenge_scalar(b"u");
+        let u_inv: Scalar = Option::from(u.invert()).ok_or(ZkError::ChallengeMismatch)?;
+        for i in 0..n {
+            a[i] = a[i] * u + a[n + i] * u_inv;
+            b[i] = b[i] * u_inv + b[n + i] * u;
+            gs[i] = gs[i] * u_inv + gs[n + i] * u;
+            hs[i] = hs[i] * u + hs[n + i] * u_inv;
+        }
+        for vector in [&mut a, &mut b] {
+            vector[n..].zeroize();
+            vector.truncate(n);
+        }
+        gs.truncate(n);
+        hs.truncate(n);
+        l_vec.push(l);
+        r_vec.push(r);
+    }
+    let (ipa_a, ipa_b) = (a[0], b[0]);
+    a.zeroize();
+    b.zeroize();
+    Ok((l_vec, r_vec, ipa_a, ipa_b))
+}
+
+/// Returns the coefficient `s_i` of `G_i` in the folded generator, the product of `u_k` or
+/// `u_k^-1` depending on bit `k` of `i`, counted from the most significant.
+fn folding_scalars(u: &[Scalar], u_inv: &[Scalar]) -> Vec<Scalar> {
+    let rounds = u.len();
+    let mut s = Vec::with_capacity(1 << rounds);
+    s.push(
+        u_inv
+            .iter()
+            .fold(Scalar::ONE, |product, u_inv| product * u_inv),
+    );
+    for i in 1usize..1 << rounds {
+        let bit = (usize::BITS - 1 - i.leading_zeros()) as usize;
+        let k = 1 << bit;
+        s.push(s[i - k] * u[rounds - 1 - bit].square());
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blindings(m: usize) -> Vec<SecretScalar> {
+        (0..m)
+            .map(|_| SecretScalar::random(&mut rand::thread_rng()))
+            .collect()
+    }
+
+    fn prove(values: &[u64], bits: usize) -> (RangeProof, Vec<PedersenCommitment>) {
+        RangeProof::prove("sid", 1, values, &blindings(values.len()), bits).unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        for (values, bits) in [
+            (vec![0], 8),
+            (vec![255], 8),
+            (vec![12345], 16),
+            (vec![u64::MAX], 64),
+            (vec![1, 2], 32),
+            (vec![0, 255, 7, 128], 8),
+        ] {
+            let (proof, commitments) = prove(&values, bits);
+            assert_eq!(
+                proof.l.len(),
+                (bits * values.len()).trailing_zeros() as usize
+            );
+            assert!(proof.verify("sid", 1, &commitments, bits).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_commitments() {
+        let blindings = blindings(2);
+        let (_, commitments) = RangeProof::prove("sid", 1, &[3, 4], &blindings, 8).unwrap();
+        for (commitment, (value, blinding)) in
+            commitments.iter().zip([3u64, 4].iter().zip(&blindings))
+        {
+            assert!(commitment.opens_to(&Scalar::from(*value), blinding.expose_secret()));
+        }
+    }
+
+    #[test]
+    fn test_prove_rejects_invalid_input() {
+        for (values, bits) in [
+            (vec![256], 8),
+            (vec![1 << 16], 16),
+            (vec![1, 2, 3], 8),
+            (vec![], 8),
+            (vec![1], 7),
+            (vec![1], 128),
+        ] {
+            assert!(matches!(
+                RangeProof::prove("sid", 1, &values, &blindings(values.len()), bits),
+                Err(ZkError::InvalidInput(_))
+            ));
+        }
+        assert!(matches!(
+            RangeProof::prove("sid", 1, &[1, 2], &blindings(1), 8),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_failed_wrong_sid_or_pid() {
+        let (proof, commitments) = prove(&[42], 8);
+        assert_eq!(
+            proof.verify("abc", 1, &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert_eq!(
+            proof.verify("sid", 2, &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_other_statement() {
+        let (proof, commitments) = prove(&[42, 43], 8);
+        let (_, other) = prove(&[42, 43], 8);
+        assert_eq!(
+            proof.verify("sid", 1, &other, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        let swapped = [commitments[1], commitments[0]];
+        assert_eq!(
+            proof.verify("sid", 1, &swapped, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        // The proof size fixes n*m.
+        assert!(matches!(
+            proof.verify("sid", 1, &commitments, 16),
+            Err(ZkError::Encoding(_))
+        ));
+        assert!(matches!(
+            proof.verify("sid", 1, &commitments[..1], 8),
+            Err(ZkError::Encoding(_))
+        ));
+        let (proof, commitments) = prove(&[42], 16);
+        assert_eq!(
+            proof.verify("sid", 1, &[commitments[0], commitments[0]], 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_out_of_range() {
+        // Commitments to values outside the range cannot be proven by an honest prover, so
+        // commit to 2^8 by shifting an in-range commitment by G.
+        let (proof, commitments) = prove(&[255], 8);
+        let shifted = PedersenCommitment::new(*commitments[0].point() + ProjectivePoint::GENERATOR);
+        assert_eq!(
+            proof.verify("sid", 1, &[shifted], 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_failed_tampered() {
+        let (proof, commitments) = prove(&[42, 7], 8);
+        let mut tampered = proof.clone();
+        tampered.t_hat += Scalar::ONE;
+        assert_eq!(
+            tampered.verify("sid", 1, &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.ipa_a += Scalar::ONE;
+        assert_eq!(
+            tampered.verify("sid", 1, &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.l.swap(0, 1);
+        assert_eq!(
+            tampered.verify("sid", 1, &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+        let mut tampered = proof;
+        tampered.t1 = ProjectivePoint::IDENTITY;
+        assert_eq!(
+            tampered.verify("sid", 1, &commitments, 8),
+            Err(ZkError::IdentityPoint)
+        );
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let proofs = [prove(&[1], 8), prove(&[2, 3], 8), prove(&[4, 5, 6, 7], 8)];
+        let mut items: Vec<_> = proofs
+            .iter()
+            .map(|(proof, commitments)| ("sid", 1, commitments.as_slice(), proof))
+            .collect();
+        assert_eq!(RangeProof::batch_verify(&items, 8), Ok(()));
+        assert_eq!(RangeProof::batch_verify(&[], 8), Ok(()));
+
+        items[1].1 = 2;
+        assert_eq!(RangeProof::batch_verify(&items, 8), Err(1));
+        items[1].1 = 1;
+        let other = [proofs[2].1[0]];
+        items[2].2 = &other;
+        assert_eq!(RangeProof::batch_verify(&items, 8), Err(2));
+    }
+
+    #[test]
+    fn test_batch_verify_reports_first_of_two_invalid() {
+        let proofs = [
+            prove(&[1], 8),
+            prove(&[2], 8),
+            prove(&[3], 8),
+            prove(&[4], 8),
+        ];
+        let mut truncated = proofs[3].0.clone();
+        truncated.l.pop();
+        truncated.r.pop();
+        let mut items: Vec<_> = proofs
+            .iter()
+            .map(|(proof, commitments)| ("sid", 1, commitments.as_slice(), proof))
+            .collect();
+        // Item 1 fails only the equation, item 3 already fails with too few rounds.
+        items[1].0 = "other";
+        items[3].3 = &truncated;
+        assert_eq!(RangeProof::batch_verify(&items, 8), Err(1));
+    }
+
+    #[test]
+    fn test_bytes() {
+        let (proof, commitments) = prove(&[42], 64);
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 688);
+        let decoded = RangeProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify("sid", 1, &commitments, 64).is_ok());
+        assert_eq!(prove(&[1, 2], 64).0.to_bytes().len(), 754);
+
+        assert!(matches!(
+            RangeProof::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ZkError::Encoding(_))
+        ));
+        let mut bad = bytes.clone();
+        bad[1..POINT_LEN].fill(0xff);
+        assert_eq!(RangeProof::from_bytes(&bad), Err(ZkError::MalformedPoint));
+        let mut bad = bytes;
+        bad[4 * POINT_LEN..4 * POINT_LEN + SCALAR_LEN].fill(0xff);
+        assert_eq!(
+            RangeProof::from_bytes(&bad),
+            Err(ZkError::NonCanonicalScalar)
+        );
+    }
+
+    #[test]
+    fn test_transcript_digest() {
+        let transcript = Transcript::<sha3::Sha3_256>::with_digest(b"test");
+        let (proof, commitments) = RangeProof::prove_with_transcript(
+            &mut rand::thread_rng(),
+            &mut transcript.clone(),
+            &[42],
+            &blindings(1),
+            8,
+        )
+        .unwrap();
+        assert!(proof
+            .verify_with_transcript(&mut transcript.clone(), &commitments, 8)
+            .is_ok());
+        assert_eq!(
+            proof.verify_with_transcript(&mut Transcript::new(b"test"), &commitments, 8),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use k256::{
+    elliptic_curve::{
+        rand_core::CryptoRngCore,
+        zeroize::{Zeroize, ZeroizeOnDrop},
+        Field,
+    },
+    Scalar,
+};
+
+/// A secret scalar, such as a private key or a proof nonce, that is wiped from memory when
+/// dropped.
+///
+/// The `Debug` output is redacted and the wrapper implements neither `Display` nor serde, so
+/// the secret cannot end up in logs or messages by accident. Equality is checked in constant
+/// time. Arithmetic goes through [`SecretScalar::expose_secret`].
+///
+/// Prefer [`SecretScalar::random`] over wrapping an existing scalar with [`SecretScalar::new`]:
+/// scalars are `Copy`, so the value passed to `new` may leave copies behind that are not wiped.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::SecretScalar;
+/// # use k256::ProjectivePoint;
+/// let x = SecretScalar::random(&mut rand::thread_rng());
+/// let y = ProjectivePoint::GENERATOR * x.expose_secret();
+/// assert_eq!(format!("{:?}", x), "SecretScalar(<redacted>)");
+/// ```
+#[derive(Clone)]
+pub struct SecretScalar<F: Zeroize = Scalar>(F);
+
+impl<F: Field + Zeroize> SecretScalar<F> {
+    /// Wraps `scalar`.
+    pub fn new(scalar: F) -> Self {
+        Self(scalar)
+    }
+
+    /// Generates a uniformly random secret scalar with the given cryptographically secure RNG.
+    pub fn random(rng: &mut (impl CryptoRngCore + ?Sized)) -> Self {
+        Self(F::random(rng))
+    }
+
+    /// Returns the secret value.
+    pub fn expose_secret(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: Field + Zeroize> From<F> for SecretScalar<F> {
+    fn from(scalar: F) -> Self {
+        Self::new(scalar)
+    }
+}
+
+impl<F: Zeroize> fmt::Debug for SecretScalar<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretScalar(<redacted>)")
+    }
+}
+
+impl<F: Field + Zeroize> PartialEq for SecretScalar<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl<F: Field + Zeroize> Eq for SecretScalar<F> {}
+
+impl<F: Zeroize> Drop for SecretScalar<F> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<F: Zeroize> ZeroizeOnDrop for SecretScalar<F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let x = SecretScalar::new(Scalar::from(42u64));
+        let debug = format!("{:?}", x);
+        assert_eq!(debug, "SecretScalar(<redacted>)");
+        assert!(!debug.contains("2a"));
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut scalar = Scalar::from(42u64);
+        scalar.zeroize();
+        assert_eq!(scalar, Scalar::ZERO);
+
+        let x = SecretScalar::new(Scalar::from(42u64));
+        assert_eq!(x.clone(), x);
+        assert_ne!(x, SecretScalar::new(Scalar::ONE));
+    }
+}
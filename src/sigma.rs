@@ -0,0 +1,795 @@
+//! Sigma protocols and their Fiat-Shamir transformation.
+//!
+//! A [`SigmaProtocol`] is a three-move proof of knowledge: the prover sends a commitment, the
+//! verifier replies with a random challenge and the prover answers with a response. A statement
+//! type implements the protocol once; [`SigmaProof`] turns it into a non-interactive proof by
+//! deriving the challenge from a [`Transcript`], [`And`] proves several statements under a
+//! single challenge and [`Or`] proves one of several statements without revealing which.
+//!
+//! [`DLogProof`](crate::DLogProof) is the Fiat-Shamir transformation of [`Schnorr`] and
+//! [`DLEqProof`](crate::DLEqProof) the one of [`ChaumPedersen`] and
+//! [`OpeningProof`](crate::OpeningProof) the one of [`Okamoto`].
+//!
+//! ```
+//! use k256::ProjectivePoint;
+//! use zk_proof::{
+//!     sigma::{And, ChaumPedersen, Schnorr, SigmaProof},
+//!     generate_random_number, SecretScalar, Transcript,
+//! };
+//!
+//! // Prove knowledge of x1 and x2 with y1 = x1*G, y2 = x2*G and z2 = x2*H at once.
+//! let g = ProjectivePoint::GENERATOR;
+//! let h = g * generate_random_number();
+//! let x1 = SecretScalar::random(&mut rand::thread_rng());
+//! let x2 = SecretScalar::random(&mut rand::thread_rng());
+//! let statement = And(
+//!     Schnorr::new(g, g * x1.expose_secret()),
+//!     ChaumPedersen::new(g, h, g * x2.expose_secret(), h * x2.expose_secret()),
+//! );
+//!
+//! let transcript = Transcript::new(b"my-protocol");
+//! let proof = SigmaProof::prove(
+//!     &mut rand::thread_rng(),
+//!     &mut transcript.clone(),
+//!     &statement,
+//!     &(x1, x2),
+//! )
+//! .unwrap();
+//! assert!(proof.verify(&mut transcript.clone(), &statement).is_ok());
+//! ```
+
+use k256::elliptic_curve::{rand_core::CryptoRngCore, Field, PrimeField};
+
+use crate::{
+    group::ensure_non_identity, hash::ZkDigest, SecretScalar, Transcript, ZkError, ZkGroup,
+};
+
+/// A three-move public-coin proof of knowledge, implemented by the statement being proven.
+///
+/// Implementations must be complete (honest responses verify), special sound (a witness can be
+/// extracted from two accepting transcripts with the same commitment and different challenges)
+/// and special honest-verifier zero-knowledge ([`SigmaProtocol::simulate`] produces transcripts
+/// distributed like honest ones).
+pub trait SigmaProtocol {
+    /// The field challenges are drawn from.
+    type Scalar: PrimeField;
+    /// The secret the prover knows.
+    type Witness;
+    /// The prover's first message.
+    type Commitment;
+    /// The randomness behind the commitment, consumed by the response.
+    type Nonce;
+    /// The prover's answer to the challenge.
+    type Response;
+
+    /// The name binding challenges to this protocol.
+    const NAME: &'static [u8];
+
+    /// Draws the nonce and computes the commitment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if the witness does not fit the statement.
+    fn commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError>;
+
+    /// Appends the statement and `commitment` to `transcript`.
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment);
+
+    /// Derives the Fiat-Shamir challenge for `commitment` from `transcript`.
+    fn challenge<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        commitment: &Self::Commitment,
+    ) -> Self::Scalar {
+        transcript.append_message(b"proof", Self::NAME);
+        self.absorb(transcript, commitment);
+        transcript.challenge_scalar(b"challenge")
+    }
+
+    /// Answers `challenge` with the witness and the nonce of the commitment.
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response;
+
+    /// Checks that `(commitment, challenge, response)` is an accepting transcript.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::IdentityPoint`] for degenerate statements or commitments and
+    /// [`ZkError::ChallengeMismatch`] if the transcript does not verify.
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError>;
+
+    /// Produces an accepting transcript for `challenge` without the witness.
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response);
+}
+
+/// A non-interactive proof for the statement `S` with a Fiat-Shamir challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigmaProof<S: SigmaProtocol> {
+    commitment: S::Commitment,
+    response: S::Response,
+}
+
+impl<S: SigmaProtocol> SigmaProof<S> {
+    /// Creates a proof from its commitment and response.
+    pub fn new(commitment: S::Commitment, response: S::Response) -> Self {
+        Self {
+            commitment,
+            response,
+        }
+    }
+
+    /// Returns the commitment.
+    pub fn commitment(&self) -> &S::Commitment {
+        &self.commitment
+    }
+
+    /// Returns the response.
+    pub fn response(&self) -> &S::Response {
+        &self.response
+    }
+
+    /// Proves `statement` with `witness`, deriving the challenge from `transcript`.
+    ///
+    /// The verifier must pass a transcript in the same state to [`SigmaProof::verify`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`SigmaProtocol::commit`].
+    pub fn prove<D: ZkDigest>(
+        rng: &mut (impl CryptoRngCore + ?Sized),
+        transcript: &mut Transcript<D>,
+        statement: &S,
+        witness: &S::Witness,
+    ) -> Result<Self, ZkError> {
+        let (commitment, nonce) = statement.commit(witness, rng)?;
+        let challenge = statement.challenge(transcript, &commitment);
+        let response = statement.respond(witness, nonce, &challenge);
+        Ok(Self::new(commitment, response))
+    }
+
+    /// Verifies the proof for `statement`, deriving the challenge from `transcript`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`SigmaProtocol::verify`].
+    pub fn verify<D: ZkDigest>(
+        &self,
+        transcript: &mut Transcript<D>,
+        statement: &S,
+    ) -> Result<(), ZkError> {
+        let challenge = statement.challenge(transcript, &self.commitment);
+        statement.verify(&self.commitment, &challenge, &self.response)
+    }
+}
+
+/// The conjunction of two statements, proven with one shared challenge.
+///
+/// Nest `And` to combine more statements, e.g. `And(a, And(b, c))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A, B> SigmaProtocol for And<A, B>
+where
+    A: SigmaProtocol,
+    B: SigmaProtocol<Scalar = A::Scalar>,
+{
+    type Scalar = A::Scalar;
+    type Witness = (A::Witness, B::Witness);
+    type Commitment = (A::Commitment, B::Commitment);
+    type Nonce = (A::Nonce, B::Nonce);
+    type Response = (A::Response, B::Response);
+
+    const NAME: &'static [u8] = b"And";
+
+    fn commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError> {
+        let (a_commitment, a_nonce) = self.0.commit(&witness.0, rng)?;
+        let (b_commitment, b_nonce) = self.1.commit(&witness.1, rng)?;
+        Ok(((a_commitment, b_commitment), (a_nonce, b_nonce)))
+    }
+
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment) {
+        transcript.append_message(b"proof", A::NAME);
+        self.0.absorb(transcript, &commitment.0);
+        transcript.append_message(b"proof", B::NAME);
+        self.1.absorb(transcript, &commitment.1);
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response {
+        (
+            self.0.respond(&witness.0, nonce.0, challenge),
+            self.1.respond(&witness.1, nonce.1, challenge),
+        )
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError> {
+        self.0.verify(&commitment.0, challenge, &response.0)?;
+        self.1.verify(&commitment.1, challenge, &response.1)
+    }
+
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response) {
+        let (a_commitment, a_response) = self.0.simulate(challenge, rng);
+        let (b_commitment, b_response) = self.1.simulate(challenge, rng);
+        ((a_commitment, b_commitment), (a_response, b_response))
+    }
+}
+
+/// The disjunction of statements of one kind, proven with the witness of a single branch.
+///
+/// This is the Cramer-Damgård-Schoenmakers composition: the prover simulates every branch it has
+/// no witness for with a challenge of its choice, and answers the remaining branch with the
+/// verifier's challenge minus the simulated ones. The verifier checks that the branch challenges
+/// sum to its challenge and cannot tell which branch was real.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Or<S>(pub Vec<S>);
+
+impl<S: SigmaProtocol> SigmaProtocol for Or<S> {
+    type Scalar = S::Scalar;
+    /// The index of the branch the prover knows the witness of, and that witness.
+    type Witness = (usize, S::Witness);
+    type Commitment = Vec<S::Commitment>;
+    /// The nonce of the real branch and the challenges and responses of the simulated ones.
+    type Nonce = (S::Nonce, Vec<(S::Scalar, S::Response)>);
+    /// The challenge and response of every branch.
+    type Response = Vec<(S::Scalar, S::Response)>;
+
+    const NAME: &'static [u8] = b"Or";
+
+    /// Commits to the real branch and simulates the others.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if the witness index is out of range.
+    fn commit(
+        &self,
+        witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError> {
+        let (index, witness) = witness;
+        if *index >= self.0.len() {
+            return Err(ZkError::InvalidInput(format!(
+                "witness index {} out of range for {} branches",
+                index,
+                self.0.len()
+            )));
+        }
+        let mut commitments = Vec::with_capacity(self.0.len());
+        let mut simulated = Vec::with_capacity(self.0.len() - 1);
+        let mut nonce = None;
+        for (i, statement) in self.0.iter().enumerate() {
+            if i == *index {
+                let (commitment, r) = statement.commit(witness, rng)?;
+                commitments.push(commitment);
+                nonce = Some(r);
+            } else {
+                let c = S::Scalar::random(&mut *rng);
+                let (commitment, response) = statement.simulate(&c, rng);
+                commitments.push(commitment);
+                simulated.push((c, response));
+            }
+        }
+        let nonce = nonce.expect("the real branch is committed");
+        Ok((commitments, (nonce, simulated)))
+    }
+
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment) {
+        transcript.append_u32(b"branches", self.0.len() as u32);
+        for (statement, commitment) in self.0.iter().zip(commitment) {
+            transcript.append_message(b"proof", S::NAME);
+            statement.absorb(transcript, commitment);
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response {
+        let (index, witness) = witness;
+        let (r, mut responses) = nonce;
+        let c = responses
+            .iter()
+            .fold(*challenge, |c, (simulated, _)| c - simulated);
+        let response = self.0[*index].respond(witness, r, &c);
+        responses.insert(*index, (c, response));
+        responses
+    }
+
+    /// # Errors
+    ///
+    /// Additionally returns [`ZkError::Encoding`] if the number of commitments or responses
+    /// differs from the number of branches, or there are no branches.
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError> {
+        if self.0.is_empty() {
+            return Err(ZkError::Encoding("no branches".to_string()));
+        }
+        if commitment.len() != self.0.len() || response.len() != self.0.len() {
+            return Err(ZkError::Encoding(format!(
+                "expected {} branches, got {} commitments and {} responses",
+                self.0.len(),
+                commitment.len(),
+                response.len()
+            )));
+        }
+        for ((statement, commitment), (c, response)) in self.0.iter().zip(commitment).zip(response)
+        {
+            statement.verify(commitment, c, response)?;
+        }
+        let sum = response.iter().fold(S::Scalar::ZERO, |sum, (c, _)| sum + c);
+        if sum != *challenge {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response) {
+        let mut rest = *challenge;
+        let mut commitments = Vec::with_capacity(self.0.len());
+        let mut responses = Vec::with_capacity(self.0.len());
+        for (i, statement) in self.0.iter().enumerate() {
+            let c = if i + 1 == self.0.len() {
+                rest
+            } else {
+                S::Scalar::random(&mut *rng)
+            };
+            rest -= c;
+            let (commitment, response) = statement.simulate(&c, rng);
+            commitments.push(commitment);
+            responses.push((c, response));
+        }
+        (commitments, responses)
+    }
+}
+
+/// Knowledge of `x` with `y = x*base`: commitment `t = r*base`, response `s = r + c*x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schnorr<P> {
+    base: P,
+    y: P,
+}
+
+impl<P: ZkGroup> Schnorr<P> {
+    /// Creates the statement `y = x*base`.
+    pub fn new(base: P, y: P) -> Self {
+        Self { base, y }
+    }
+}
+
+impl<P: ZkGroup> SigmaProtocol for Schnorr<P> {
+    type Scalar = P::Scalar;
+    type Witness = SecretScalar<P::Scalar>;
+    type Commitment = P;
+    type Nonce = SecretScalar<P::Scalar>;
+    type Response = P::Scalar;
+
+    const NAME: &'static [u8] = b"DLogProof";
+
+    fn commit(
+        &self,
+        _witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError> {
+        let r = SecretScalar::random(rng);
+        Ok((self.base * r.expose_secret(), r))
+    }
+
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment) {
+        for point in [self.base, self.y, *commitment] {
+            transcript.append_point(b"point", &point);
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response {
+        *challenge * witness.expose_secret() + nonce.expose_secret()
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError> {
+        ensure_non_identity(&[self.base, self.y, *commitment])?;
+        if self.base * response != *commitment + self.y * challenge {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response) {
+        let s = P::Scalar::random(rng);
+        (self.base * s - self.y * challenge, s)
+    }
+}
+
+/// Equality of discrete logarithms, `y = x*g` and `z = x*h`: commitments `t1 = r*g` and
+/// `t2 = r*h`, response `s = r + c*x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaumPedersen<P> {
+    g: P,
+    h: P,
+    y: P,
+    z: P,
+}
+
+impl<P: ZkGroup> ChaumPedersen<P> {
+    /// Creates the statement `y = x*g` and `z = x*h`.
+    pub fn new(g: P, h: P, y: P, z: P) -> Self {
+        Self { g, h, y, z }
+    }
+}
+
+impl<P: ZkGroup> SigmaProtocol for ChaumPedersen<P> {
+    type Scalar = P::Scalar;
+    type Witness = SecretScalar<P::Scalar>;
+    type Commitment = (P, P);
+    type Nonce = SecretScalar<P::Scalar>;
+    type Response = P::Scalar;
+
+    const NAME: &'static [u8] = b"DLEqProof";
+
+    fn commit(
+        &self,
+        _witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError> {
+        let r = SecretScalar::random(rng);
+        Ok(((self.g * r.expose_secret(), self.h * r.expose_secret()), r))
+    }
+
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment) {
+        for point in [self.g, self.h, self.y, self.z, commitment.0, commitment.1] {
+            transcript.append_point(b"point", &point);
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response {
+        *challenge * witness.expose_secret() + nonce.expose_secret()
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError> {
+        let (t1, t2) = *commitment;
+        ensure_non_identity(&[self.g, self.h, self.y, self.z, t1, t2])?;
+        if self.g * response != t1 + self.y * challenge
+            || self.h * response != t2 + self.z * challenge
+        {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response) {
+        let s = P::Scalar::random(rng);
+        let t1 = self.g * s - self.y * challenge;
+        let t2 = self.h * s - self.z * challenge;
+        ((t1, t2), s)
+    }
+}
+
+/// Knowledge of an opening `(m, r)` of `c = m*g + r*h` (Okamoto): commitment
+/// `t = r_m*g + r_r*h`, responses `s_m = r_m + e*m` and `s_r = r_r + e*r` for the challenge `e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Okamoto<P> {
+    g: P,
+    h: P,
+    c: P,
+}
+
+impl<P: ZkGroup> Okamoto<P> {
+    /// Creates the statement `c = m*g + r*h`.
+    pub fn new(g: P, h: P, c: P) -> Self {
+        Self { g, h, c }
+    }
+}
+
+impl<P: ZkGroup> SigmaProtocol for Okamoto<P> {
+    type Scalar = P::Scalar;
+    type Witness = (SecretScalar<P::Scalar>, SecretScalar<P::Scalar>);
+    type Commitment = P;
+    type Nonce = (SecretScalar<P::Scalar>, SecretScalar<P::Scalar>);
+    type Response = (P::Scalar, P::Scalar);
+
+    const NAME: &'static [u8] = b"OpeningProof";
+
+    fn commit(
+        &self,
+        _witness: &Self::Witness,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> Result<(Self::Commitment, Self::Nonce), ZkError> {
+        let r_m = SecretScalar::random(&mut *rng);
+        let r_r = SecretScalar::random(rng);
+        let t = self.g * r_m.expose_secret() + self.h * r_r.expose_secret();
+        Ok((t, (r_m, r_r)))
+    }
+
+    fn absorb<D: ZkDigest>(&self, transcript: &mut Transcript<D>, commitment: &Self::Commitment) {
+        for point in [self.g, self.h, self.c, *commitment] {
+            transcript.append_point(b"point", &point);
+        }
+    }
+
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: Self::Nonce,
+        challenge: &Self::Scalar,
+    ) -> Self::Response {
+        let (m, r) = witness;
+        let (r_m, r_r) = nonce;
+        (
+            *challenge * m.expose_secret() + r_m.expose_secret(),
+            *challenge * r.expose_secret() + r_r.expose_secret(),
+        )
+    }
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        challenge: &Self::Scalar,
+        response: &Self::Response,
+    ) -> Result<(), ZkError> {
+        ensure_non_identity(&[self.g, self.h, self.c, *commitment])?;
+        let (s_m, s_r) = response;
+        if self.g * s_m + self.h * s_r != *commitment + self.c * challenge {
+            return Err(ZkError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    fn simulate(
+        &self,
+        challenge: &Self::Scalar,
+        rng: &mut (impl CryptoRngCore + ?Sized),
+    ) -> (Self::Commitment, Self::Response) {
+        let s_m = P::Scalar::random(&mut *rng);
+        let s_r = P::Scalar::random(rng);
+        (self.g * s_m + self.h * s_r - self.c * challenge, (s_m, s_r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_random_number, DLEqProof, DLogProof};
+    use k256::{ProjectivePoint, Scalar};
+
+    const G: ProjectivePoint = ProjectivePoint::GENERATOR;
+
+    fn schnorr() -> (Schnorr<ProjectivePoint>, SecretScalar) {
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        (Schnorr::new(G, G * x.expose_secret()), x)
+    }
+
+    fn chaum_pedersen() -> (ChaumPedersen<ProjectivePoint>, SecretScalar) {
+        let h = G * generate_random_number();
+        let x = SecretScalar::random(&mut rand::thread_rng());
+        let (y, z) = (G * x.expose_secret(), h * x.expose_secret());
+        (ChaumPedersen::new(G, h, y, z), x)
+    }
+
+    fn prove_and_verify<S: SigmaProtocol>(
+        statement: &S,
+        witness: &S::Witness,
+    ) -> Result<(), ZkError> {
+        let transcript = Transcript::new(b"test");
+        let proof = SigmaProof::prove(
+            &mut rand::thread_rng(),
+            &mut transcript.clone(),
+            statement,
+            witness,
+        )?;
+        proof.verify(&mut transcript.clone(), statement)
+    }
+
+    #[test]
+    fn test_completeness() {
+        let (statement, x) = schnorr();
+        assert!(prove_and_verify(&statement, &x).is_ok());
+        let (statement, x) = chaum_pedersen();
+        assert!(prove_and_verify(&statement, &x).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_witness() {
+        let (statement, _) = schnorr();
+        let (_, other) = schnorr();
+        assert_eq!(
+            prove_and_verify(&statement, &other),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_simulate() {
+        let mut rng = rand::thread_rng();
+        let c = generate_random_number();
+        let (statement, _) = schnorr();
+        let (t, s) = statement.simulate(&c, &mut rng);
+        assert!(statement.verify(&t, &c, &s).is_ok());
+        let (statement, _) = chaum_pedersen();
+        let (t, s) = statement.simulate(&c, &mut rng);
+        assert!(statement.verify(&t, &c, &s).is_ok());
+        let statement = Okamoto::new(
+            G,
+            G * generate_random_number(),
+            G * generate_random_number(),
+        );
+        let (t, s) = statement.simulate(&c, &mut rng);
+        assert!(statement.verify(&t, &c, &s).is_ok());
+    }
+
+    #[test]
+    fn test_special_soundness() {
+        // Two accepting responses to one commitment reveal x = (s1 - s2) / (c1 - c2).
+        let mut rng = rand::thread_rng();
+        let (statement, x) = schnorr();
+        let (t, r) = statement.commit(&x, &mut rng).unwrap();
+        let r2 = SecretScalar::new(*r.expose_secret());
+        let (c1, c2) = (generate_random_number(), generate_random_number());
+        let s1 = statement.respond(&x, r, &c1);
+        let s2 = statement.respond(&x, r2, &c2);
+        assert!(statement.verify(&t, &c1, &s1).is_ok());
+        assert!(statement.verify(&t, &c2, &s2).is_ok());
+        let extracted = (s1 - s2) * (c1 - c2).invert().unwrap();
+        assert_eq!(&extracted, x.expose_secret());
+    }
+
+    #[test]
+    fn test_and() {
+        let (a, x1) = schnorr();
+        let (b, x2) = chaum_pedersen();
+        let (c, x3) = schnorr();
+        let statement = And(a, And(b, c));
+        let witness = (x1, (x2, x3));
+        assert!(prove_and_verify(&statement, &witness).is_ok());
+
+        // Every conjunct needs its witness.
+        let wrong = And(a, And(b, schnorr().0));
+        assert_eq!(
+            prove_and_verify(&wrong, &witness),
+            Err(ZkError::ChallengeMismatch)
+        );
+
+        let (t, s) = statement.simulate(&Scalar::ONE, &mut rand::thread_rng());
+        assert!(statement.verify(&t, &Scalar::ONE, &s).is_ok());
+    }
+
+    #[test]
+    fn test_and_shares_one_challenge() {
+        // Proofs of the conjuncts cannot be recombined into a proof of the conjunction.
+        let (a, x1) = schnorr();
+        let (b, x2) = schnorr();
+        let transcript = Transcript::new(b"test");
+        let mut rng = rand::thread_rng();
+        let pa = SigmaProof::prove(&mut rng, &mut transcript.clone(), &a, &x1).unwrap();
+        let pb = SigmaProof::prove(&mut rng, &mut transcript.clone(), &b, &x2).unwrap();
+        let combined = SigmaProof::<And<_, _>>::new(
+            (*pa.commitment(), *pb.commitment()),
+            (*pa.response(), *pb.response()),
+        );
+        assert_eq!(
+            combined.verify(&mut transcript.clone(), &And(a, b)),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_or() {
+        let (a, _) = schnorr();
+        let (b, x) = schnorr();
+        let statement = Or(vec![a, b, schnorr().0]);
+        assert!(prove_and_verify(&statement, &(1, x.clone())).is_ok());
+        assert_eq!(
+            prove_and_verify(&statement, &(0, x.clone())),
+            Err(ZkError::ChallengeMismatch)
+        );
+        assert!(matches!(
+            prove_and_verify(&statement, &(3, x.clone())),
+            Err(ZkError::InvalidInput(_))
+        ));
+
+        // Disjunctions compose with conjunctions.
+        let (c, _) = chaum_pedersen();
+        let (d, z) = chaum_pedersen();
+        let statement = Or(vec![And(a, c), And(b, d)]);
+        let (x_, z_) = (x.clone(), z.clone());
+        assert!(prove_and_verify(&statement, &(1, (x, z))).is_ok());
+        assert_eq!(
+            prove_and_verify(&statement, &(0, (x_, z_))),
+            Err(ZkError::ChallengeMismatch)
+        );
+
+        let c = generate_random_number();
+        let (t, s) = statement.simulate(&c, &mut rand::thread_rng());
+        assert!(statement.verify(&t, &c, &s).is_ok());
+    }
+
+    #[test]
+    fn test_matches_dlog_and_dleq_proofs() {
+        let (statement, x) = schnorr();
+        let y = G * x.expose_secret();
+        let dlog_proof = DLogProof::prove("sid", 1, &x, y);
+        let proof = SigmaProof::<Schnorr<_>>::new(*dlog_proof.t(), *dlog_proof.s());
+        let mut transcript = crate::transcript::session::<sha2::Sha256>("sid", 1);
+        assert!(proof.verify(&mut transcript, &statement).is_ok());
+
+        let h = G * generate_random_number();
+        let (y, z) = (G * x.expose_secret(), h * x.expose_secret());
+        let dleq_proof = DLEqProof::prove("sid", 1, &x, h, y, z);
+        let proof = SigmaProof::<ChaumPedersen<_>>::new(
+            (*dleq_proof.t1(), *dleq_proof.t2()),
+            *dleq_proof.s(),
+        );
+        let mut transcript = crate::transcript::session::<sha2::Sha256>("sid", 1);
+        assert!(proof
+            .verify(&mut transcript, &ChaumPedersen::new(G, h, y, z))
+            .is_ok());
+    }
+}
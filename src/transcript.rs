@@ -0,0 +1,224 @@
+//! Domain-separated Fiat-Shamir transcripts.
+//!
+//! A [`Transcript`] absorbs labelled messages and squeezes challenges from everything absorbed
+//! so far. Every operation is framed unambiguously:
+//!
+//! ```text
+//! append:    0x01 || len(label) || label || len(message) || message
+//! challenge: 0x02 || len(label) || label || len(output)
+//! ```
+//!
+//! where lengths are 8-byte big-endian integers. The transcript starts with the protocol tag
+//! `zk_proof transcript v1` followed by the caller's domain separator, so challenges of
+//! different proofs, or of the same proof in different protocols, never coincide. After a
+//! challenge is squeezed it is absorbed back, so later challenges depend on earlier ones.
+//!
+//! Transcripts hash with SHA-256 by default and can be instantiated with any other
+//! [`ZkDigest`], e.g. to match the hash function of another implementation:
+//!
+//! ```
+//! # use zk_proof::Transcript;
+//! use sha2::Sha512;
+//!
+//! let mut transcript = Transcript::<Sha512>::with_digest(b"my-protocol");
+//! transcript.append_message(b"sid", b"session");
+//! let c: k256::Scalar = transcript.challenge_scalar(b"c");
+//! ```
+
+use k256::elliptic_curve::{group::GroupEncoding, PrimeField};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    group::reduce_be_bytes,
+    hash::{wide_len, ZkDigest},
+    ZkGroup,
+};
+
+const PROTOCOL: &[u8] = b"zk_proof transcript v1";
+const APPEND: u8 = 1;
+const CHALLENGE: u8 = 2;
+
+/// A Fiat-Shamir transcript with labelled, length-prefixed messages.
+///
+/// # Example
+///
+/// ```
+/// # use zk_proof::Transcript;
+/// # use k256::{ProjectivePoint, Scalar};
+/// let mut transcript = Transcript::new(b"my-protocol");
+/// transcript.append_message(b"sid", b"session");
+/// transcript.append_point(b"y", &ProjectivePoint::GENERATOR);
+/// let c: Scalar = transcript.challenge_scalar(b"c");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Transcript<D = Sha256> {
+    hasher: D,
+}
+
+impl Transcript {
+    /// Starts a SHA-256 transcript for the protocol identified by `domain`.
+    pub fn new(domain: &[u8]) -> Self {
+        Self::with_digest(domain)
+    }
+}
+
+impl<D: ZkDigest> Transcript<D> {
+    /// Starts a transcript hashing with `D` for the protocol identified by `domain`.
+    pub fn with_digest(domain: &[u8]) -> Self {
+        let mut transcript = Self { hasher: D::new() };
+        transcript.append_message(b"protocol", PROTOCOL);
+        transcript.append_message(b"domain", domain);
+        transcript
+    }
+
+    /// Absorbs `message` under `label`.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        self.hasher.update([APPEND]);
+        self.absorb_framed(label);
+        self.absorb_framed(message);
+    }
+
+    /// Absorbs `value` as 4 big-endian bytes under `label`.
+    pub fn append_u32(&mut self, label: &[u8], value: u32) {
+        self.append_message(label, &value.to_be_bytes());
+    }
+
+    /// Absorbs the canonical encoding of `point` under `label`.
+    pub fn append_point<P: GroupEncoding>(&mut self, label: &[u8], point: &P) {
+        self.append_message(label, point.to_bytes().as_ref());
+    }
+
+    /// Absorbs the canonical encoding of `scalar` under `label`.
+    pub fn append_scalar<F: PrimeField>(&mut self, label: &[u8], scalar: &F) {
+        self.append_message(label, scalar.to_repr().as_ref());
+    }
+
+    /// Fills `dest` with challenge bytes derived from the transcript so far and absorbs them.
+    pub fn challenge_bytes(&mut self, label: &[u8], dest: &mut [u8]) {
+        self.hasher.update([CHALLENGE]);
+        self.absorb_framed(label);
+        self.hasher.update((dest.len() as u64).to_be_bytes());
+
+        let seed = self.hasher.clone().finalize();
+        for (counter, chunk) in dest.chunks_mut(<D as Digest>::output_size()).enumerate() {
+            let block = D::new()
+                .chain_update(&seed)
+                .chain_update((counter as u64).to_be_bytes())
+                .finalize();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.append_message(label, dest);
+    }
+
+    /// Derives a challenge scalar from the transcript so far and absorbs it.
+    ///
+    /// The scalar is reduced from 128 more bits than the group order has, like
+    /// [`hash_to_scalar`](crate::hash::hash_to_scalar), so it is uniform up to a bias of at most
+    /// `2^-128`.
+    pub fn challenge_scalar<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        let mut bytes = vec![0u8; wide_len::<F>()];
+        self.challenge_bytes(label, &mut bytes);
+        reduce_be_bytes(&bytes)
+    }
+
+    fn absorb_framed(&mut self, bytes: &[u8]) {
+        self.hasher.update((bytes.len() as u64).to_be_bytes());
+        self.hasher.update(bytes);
+    }
+}
+
+/// Starts the transcript of the proofs made with a session id and a prover id.
+pub(crate) fn session<D: ZkDigest>(sid: &str, pid: u32) -> Transcript<D> {
+    let mut transcript = Transcript::with_digest(b"zk_proof");
+    transcript.append_message(b"sid", sid.as_bytes());
+    transcript.append_u32(b"pid", pid);
+    transcript
+}
+
+/// Derives the challenge of the proof named `proof` over its statement and commitment `points`.
+pub(crate) fn challenge<P: ZkGroup, D: ZkDigest>(
+    transcript: &mut Transcript<D>,
+    proof: &[u8],
+    points: &[P],
+) -> P::Scalar {
+    transcript.append_message(b"proof", proof);
+    for point in points {
+        transcript.append_point(b"point", point);
+    }
+    transcript.challenge_scalar(b"challenge")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::Scalar;
+
+    fn squeeze<D: ZkDigest>(transcript: &mut Transcript<D>) -> Scalar {
+        transcript.challenge_scalar(b"c")
+    }
+
+    #[test]
+    fn test_known_answer() {
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_message(b"sid", b"sid");
+        transcript.append_u32(b"pid", 1);
+        let mut bytes = [0u8; 40];
+        transcript.challenge_bytes(b"c", &mut bytes);
+        assert_eq!(
+            hex::encode(bytes),
+            "fee4161443ddcf16c581fdef231fd851c1f98fe0732a6b696c2044d622ed50a9c93164b88e071def"
+        );
+        let c: Scalar = transcript.challenge_scalar(b"c");
+        assert_eq!(
+            hex::encode(c.to_bytes()),
+            "36b6ebcf82db547def039226cf0bbf0a27eafaf6c8f901bc082b0cd41ec2b5b5"
+        );
+    }
+
+    #[test]
+    fn test_messages_are_unambiguous() {
+        let mut a = Transcript::new(b"test");
+        a.append_message(b"sid", b"ab");
+        a.append_message(b"pid", b"c");
+        let mut b = Transcript::new(b"test");
+        b.append_message(b"sid", b"a");
+        b.append_message(b"pid", b"bc");
+        assert_ne!(squeeze(&mut a), squeeze(&mut b));
+
+        let mut a = Transcript::new(b"test");
+        a.append_message(b"sid", b"x");
+        let mut b = Transcript::new(b"test");
+        b.append_message(b"si", b"dx");
+        assert_ne!(squeeze(&mut a), squeeze(&mut b));
+    }
+
+    #[test]
+    fn test_digests() {
+        let mut sha256 = Transcript::new(b"test");
+        let mut sha512 = Transcript::<sha2::Sha512>::with_digest(b"test");
+        let mut sha3 = Transcript::<sha3::Sha3_256>::with_digest(b"test");
+        let mut blake2 = Transcript::<blake2::Blake2b512>::with_digest(b"test");
+        let c = squeeze(&mut sha256);
+        assert_ne!(squeeze(&mut sha512), c);
+        assert_ne!(squeeze(&mut sha3), c);
+        assert_ne!(squeeze(&mut blake2), c);
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut a = Transcript::new(b"protocol-a");
+        let mut b = Transcript::new(b"protocol-b");
+        assert_ne!(squeeze(&mut a), squeeze(&mut b));
+    }
+
+    #[test]
+    fn test_challenges_are_chained() {
+        let mut transcript = Transcript::new(b"test");
+        let first = squeeze(&mut transcript);
+        let second = squeeze(&mut transcript);
+        assert_ne!(first, second);
+
+        let mut fresh = Transcript::new(b"test");
+        assert_eq!(squeeze(&mut fresh), first);
+    }
+}
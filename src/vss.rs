@@ -0,0 +1,407 @@
+//! Feldman verifiable secret sharing over secp256k1.
+//!
+//! A dealer splits a secret `x` into `n` Shamir shares `s_i = f(i)` of a random polynomial
+//! `f(X) = a_0 + a_1*X + ... + a_(t-1)*X^(t-1)` with `a_0 = x`, so that any `t` shares reconstruct
+//! `x` and fewer reveal nothing about it. The dealer also publishes the [`FeldmanCommitment`]
+//! `A_j = a_j*G` to the coefficients, against which every party checks its share with
+//! `s_i*G == sum(i^j * A_j)`, and a [`DLogProof`] for `A_0 = x*G` showing it knows the secret.
+//!
+//! Share indices are the nonzero party ids, like the `pid` of the proofs.
+//!
+//! ```
+//! use zk_proof::{vss, SecretScalar};
+//!
+//! let x = SecretScalar::random(&mut rand::thread_rng());
+//! let (commitment, proof, shares) = vss::share("sid", 1, &x, 2, 3).unwrap();
+//!
+//! // Every party checks the broadcast commitment and its own share.
+//! assert!(commitment.verify("sid", 1, &proof).is_ok());
+//! for share in &shares {
+//!     assert!(commitment.verify_share(share).is_ok());
+//! }
+//!
+//! // Any two shares reconstruct the secret.
+//! let secret = vss::reconstruct(&shares[1..]).unwrap();
+//! assert_eq!(secret, x);
+//! ```
+
+use k256::{elliptic_curve::rand_core::CryptoRngCore, ProjectivePoint, Scalar};
+
+use crate::{DLogProof, SecretScalar, ZkError};
+
+/// The share `s_i = f(i)` of the party with index `i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretShare {
+    index: u32,
+    value: SecretScalar,
+}
+
+impl SecretShare {
+    /// Creates the share `value` of the party with index `index`.
+    pub fn new(index: u32, value: SecretScalar) -> Self {
+        Self { index, value }
+    }
+
+    /// Returns the index `i` of the party holding the share.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the share value `s_i`.
+    pub fn value(&self) -> &SecretScalar {
+        &self.value
+    }
+}
+
+/// The commitments `A_j = a_j*G` to the coefficients of a dealer's polynomial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeldmanCommitment {
+    coefficients: Vec<ProjectivePoint>,
+}
+
+impl FeldmanCommitment {
+    /// Creates a commitment from the points `A_0, ..., A_(t-1)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::Encoding`] if there are no points.
+    pub fn new(coefficients: Vec<ProjectivePoint>) -> Result<Self, ZkError> {
+        if coefficients.is_empty() {
+            return Err(ZkError::Encoding(
+                "a Feldman commitment needs at least one point".to_string(),
+            ));
+        }
+        Ok(Self { coefficients })
+    }
+
+    /// Returns the points `A_0, ..., A_(t-1)`.
+    pub fn coefficients(&self) -> &[ProjectivePoint] {
+        &self.coefficients
+    }
+
+    /// Returns the number of shares `t` needed to reconstruct the secret.
+    pub fn threshold(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Returns the public key `A_0 = x*G` of the shared secret.
+    pub fn public_key(&self) -> ProjectivePoint {
+        self.coefficients[0]
+    }
+
+    /// Returns the public share `f(index)*G = sum(index^j * A_j)` of the party with `index`.
+    pub fn share_point(&self, index: u32) -> ProjectivePoint {
+        let i = Scalar::from(index);
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(ProjectivePoint::IDENTITY, |acc, a_j| acc * i + a_j)
+    }
+
+    /// Checks that `share` is the evaluation of the committed polynomial at its index, i.e.
+    /// `s_i*G == sum(i^j * A_j)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ZkError::InvalidInput`] if the share index is zero and [`ZkError::InvalidShare`]
+    /// if the share does not match the commitment.
+    pub fn verify_share(&self, share: &SecretShare) -> Result<(), ZkError> {
+        check_index(share.index)?;
+        if ProjectivePoint::GENERATOR * share.value.expose_secret() != self.share_point(share.index)
+        {
+            return Err(ZkError::InvalidShare);
+        }
+        Ok(())
+    }
+
+    /// Verifies the dealer's proof of knowledge of the secret behind `A_0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The session id.
+    /// * `pid` - The id of the dealer.
+    /// * `proof` - The proof returned by [`share`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`DLogProof::verify`].
+    pub fn verify(&self, sid: &str, pid: u32, proof: &DLogProof) -> Result<(), ZkError> {
+        proof.verify(sid, pid, self.public_key())
+    }
+}
+
+/// Splits `secret` into shares for the parties `1..=parties`, any `threshold` of which
+/// reconstruct it.
+///
+/// # Arguments
+///
+/// * `sid` - The session id.
+/// * `pid` - The id of the dealer.
+/// * `secret` - The secret `x` to share.
+/// * `threshold` - The number of shares `t` needed to reconstruct the secret.
+/// * `parties` - The number of shares `n`.
+///
+/// # Returns
+///
+/// The commitment to broadcast, the proof of knowledge of `x` for `A_0` and the share of each
+/// party, in order of index.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] unless `1 <= threshold <= parties`.
+pub fn share(
+    sid: &str,
+    pid: u32,
+    secret: &SecretScalar,
+    threshold: usize,
+    parties: u32,
+) -> Result<(FeldmanCommitment, DLogProof, Vec<SecretShare>), ZkError> {
+    share_with_rng(
+        &mut rand::thread_rng(),
+        sid,
+        pid,
+        secret,
+        threshold,
+        parties,
+    )
+}
+
+/// Splits `secret` like [`share`] with the polynomial and the proof nonce drawn from `rng`.
+pub fn share_with_rng(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    sid: &str,
+    pid: u32,
+    secret: &SecretScalar,
+    threshold: usize,
+    parties: u32,
+) -> Result<(FeldmanCommitment, DLogProof, Vec<SecretShare>), ZkError> {
+    let (commitment, shares) = deal(rng, secret, threshold, parties)?;
+    let proof = DLogProof::prove_with_rng(rng, sid, pid, secret, commitment.public_key());
+    Ok((commitment, proof, shares))
+}
+
+/// Splits `secret` like [`share`] without proving knowledge of it.
+pub(crate) fn deal(
+    rng: &mut (impl CryptoRngCore + ?Sized),
+    secret: &SecretScalar,
+    threshold: usize,
+    parties: u32,
+) -> Result<(FeldmanCommitment, Vec<SecretShare>), ZkError> {
+    if threshold == 0 || threshold > parties as usize {
+        return Err(ZkError::InvalidInput(format!(
+            "threshold {} is not between 1 and the number of parties {}",
+            threshold, parties
+        )));
+    }
+    let polynomial: Vec<_> = std::iter::once(secret.clone())
+        .chain((1..threshold).map(|_| SecretScalar::random(&mut *rng)))
+        .collect();
+    let commitment = FeldmanCommitment {
+        coefficients: polynomial
+            .iter()
+            .map(|a_j| ProjectivePoint::GENERATOR * a_j.expose_secret())
+            .collect(),
+    };
+    let shares = (1..=parties)
+        .map(|index| SecretShare::new(index, evaluate(&polynomial, index)))
+        .collect();
+    Ok((commitment, shares))
+}
+
+/// Reconstructs the secret from shares of distinct parties by Lagrange interpolation at zero.
+///
+/// At least as many shares as the threshold must be given; fewer yield an unrelated value.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if there are no shares, or an index is zero or repeated.
+pub fn reconstruct(shares: &[SecretShare]) -> Result<SecretScalar, ZkError> {
+    if shares.is_empty() {
+        return Err(ZkError::InvalidInput("no shares".to_string()));
+    }
+    let indices: Vec<_> = shares.iter().map(SecretShare::index).collect();
+    let mut secret = SecretScalar::new(Scalar::ZERO);
+    for share in shares {
+        let lambda = lagrange_coefficient(share.index, &indices)?;
+        secret = SecretScalar::new(lambda * share.value.expose_secret() + secret.expose_secret());
+    }
+    Ok(secret)
+}
+
+/// Returns the Lagrange coefficient `lambda_i = prod_(j != i) j / (j - i)` of the party `index`
+/// for interpolating at zero from the parties `indices`.
+///
+/// # Errors
+///
+/// Returns [`ZkError::InvalidInput`] if `index` is not in `indices`, or an index is zero or
+/// repeated.
+pub fn lagrange_coefficient(index: u32, indices: &[u32]) -> Result<Scalar, ZkError> {
+    if !indices.contains(&index) {
+        return Err(ZkError::InvalidInput(format!(
+            "index {} is not among the parties",
+            index
+        )));
+    }
+    let mut numerator = Scalar::ONE;
+    let mut denominator = Scalar::ONE;
+    for (position, &j) in indices.iter().enumerate() {
+        check_index(j)?;
+        if indices[..position].contains(&j) {
+            return Err(ZkError::InvalidInput(format!("repeated index {}", j)));
+        }
+        if j != index {
+            numerator *= Scalar::from(j);
+            denominator *= Scalar::from(j) - Scalar::from(index);
+        }
+    }
+    Ok(numerator * denominator.invert().unwrap())
+}
+
+/// Evaluates the polynomial with the given coefficients at `index` with Horner's rule.
+fn evaluate(polynomial: &[SecretScalar], index: u32) -> SecretScalar {
+    let i = Scalar::from(index);
+    let mut value = SecretScalar::new(Scalar::ZERO);
+    for a_j in polynomial.iter().rev() {
+        value = SecretScalar::new(*value.expose_secret() * i + a_j.expose_secret());
+    }
+    value
+}
+
+fn check_index(index: u32) -> Result<(), ZkError> {
+    if index == 0 {
+        return Err(ZkError::InvalidInput(
+            "share indices start at 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret() -> SecretScalar {
+        SecretScalar::random(&mut rand::thread_rng())
+    }
+
+    #[test]
+    fn test_share_and_reconstruct() {
+        for (threshold, parties) in [(1, 1), (1, 3), (2, 3), (3, 3), (3, 5)] {
+            let x = secret();
+            let (commitment, proof, shares) = share("sid", 1, &x, threshold, parties).unwrap();
+            assert_eq!(commitment.threshold(), threshold);
+            assert_eq!(shares.len(), parties as usize);
+            assert_eq!(
+                commitment.public_key(),
+                ProjectivePoint::GENERATOR * x.expose_secret()
+            );
+            assert!(commitment.verify("sid", 1, &proof).is_ok());
+            for share in &shares {
+                assert!(commitment.verify_share(share).is_ok());
+            }
+            assert_eq!(reconstruct(&shares).unwrap(), x);
+            assert_eq!(reconstruct(&shares[shares.len() - threshold..]).unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_any_subset() {
+        let x = secret();
+        let (_, _, shares) = share("sid", 1, &x, 3, 5).unwrap();
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1], [1, 2, 3]] {
+            let subset: Vec<_> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(reconstruct(&subset).unwrap(), x);
+        }
+        // Fewer than threshold shares do not reveal the secret.
+        assert_ne!(reconstruct(&shares[..2]).unwrap(), x);
+    }
+
+    #[test]
+    fn test_share_point() {
+        let (commitment, _, shares) = share("sid", 1, &secret(), 3, 4).unwrap();
+        for share in &shares {
+            assert_eq!(
+                commitment.share_point(share.index()),
+                ProjectivePoint::GENERATOR * share.value().expose_secret()
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_share_failed() {
+        let (commitment, _, shares) = share("sid", 1, &secret(), 2, 3).unwrap();
+        let wrong_value = SecretShare::new(
+            1,
+            SecretScalar::new(shares[0].value().expose_secret() + Scalar::ONE),
+        );
+        assert_eq!(
+            commitment.verify_share(&wrong_value),
+            Err(ZkError::InvalidShare)
+        );
+        let wrong_index = SecretShare::new(2, shares[0].value().clone());
+        assert_eq!(
+            commitment.verify_share(&wrong_index),
+            Err(ZkError::InvalidShare)
+        );
+        let (other, _, _) = share("sid", 1, &secret(), 2, 3).unwrap();
+        assert_eq!(other.verify_share(&shares[0]), Err(ZkError::InvalidShare));
+        assert!(matches!(
+            commitment.verify_share(&SecretShare::new(0, secret())),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_failed() {
+        let (commitment, proof, _) = share("sid", 1, &secret(), 2, 3).unwrap();
+        assert_eq!(
+            commitment.verify("sid", 2, &proof),
+            Err(ZkError::ChallengeMismatch)
+        );
+        let (other, _, _) = share("sid", 1, &secret(), 2, 3).unwrap();
+        assert_eq!(
+            other.verify("sid", 1, &proof),
+            Err(ZkError::ChallengeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for (threshold, parties) in [(0, 3), (4, 3), (1, 0)] {
+            assert!(matches!(
+                share("sid", 1, &secret(), threshold, parties),
+                Err(ZkError::InvalidInput(_))
+            ));
+        }
+        assert!(matches!(
+            FeldmanCommitment::new(vec![]),
+            Err(ZkError::Encoding(_))
+        ));
+
+        let (_, _, shares) = share("sid", 1, &secret(), 2, 3).unwrap();
+        assert!(matches!(reconstruct(&[]), Err(ZkError::InvalidInput(_))));
+        assert!(matches!(
+            reconstruct(&[shares[0].clone(), shares[0].clone()]),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            lagrange_coefficient(4, &[1, 2, 3]),
+            Err(ZkError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            lagrange_coefficient(1, &[1, 0]),
+            Err(ZkError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_lagrange_coefficient() {
+        // For the parties 1 and 2, f(0) = 2*f(1) - f(2).
+        assert_eq!(
+            lagrange_coefficient(1, &[1, 2]).unwrap(),
+            Scalar::from(2u64)
+        );
+        assert_eq!(lagrange_coefficient(2, &[1, 2]).unwrap(), -Scalar::ONE);
+        assert_eq!(lagrange_coefficient(7, &[7]).unwrap(), Scalar::ONE);
+    }
+}